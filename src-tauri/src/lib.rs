@@ -5,6 +5,9 @@ use tauri::{Manager, State};
 mod mongodb_installer;
 mod mongodb_manager;
 
+// For embedders that drive MongoDB from Rust, e.g. MongoDbState::find_stream
+pub use mongodb_manager::MongoDbState;
+
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -17,18 +20,26 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
             // Initialize MongoDB state with database name
-            let mongodb_state = mongodb_manager::MongoDbState::new("app_database");
+            let mongodb_state = mongodb_manager::MongoDbState::new("app_database")
+                .with_app_name(&app.config().identifier);
             app.manage(mongodb_state);
 
             // Auto-connect if MongoDB is installed
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if mongodb_installer::is_mongodb_installed().await {
-                    let state: State<'_, mongodb_manager::MongoDbState> = app_handle.state();
-                    if let Err(e) = mongodb_manager::auto_connect(&state).await {
-                        eprintln!("Auto-connect failed: {}", e);
+                let state: State<'_, mongodb_manager::MongoDbState> = app_handle.state();
+                let outcome = if mongodb_installer::is_mongodb_installed().await {
+                    match mongodb_manager::auto_connect(&app_handle, &state).await {
+                        Ok(()) => mongodb_manager::AutoConnectOutcome::Connected,
+                        Err(e) => {
+                            eprintln!("Auto-connect failed: {}", e);
+                            mongodb_manager::AutoConnectOutcome::Failed { error: e }
+                        }
                     }
-                }
+                } else {
+                    mongodb_manager::AutoConnectOutcome::NotInstalled
+                };
+                state.set_autoconnect_outcome(outcome).await;
             });            
 
             Ok(())
@@ -38,15 +49,124 @@ pub fn run() {
             // MongoDB installation commands
             mongodb_installer::is_mongodb_installed,
             mongodb_installer::install_mongodb,
+            mongodb_installer::list_installed_versions,
+            mongodb_installer::select_active_version,
+            mongodb_installer::set_bind_ip,
+            mongodb_installer::data_directory_size,
+            mongodb_installer::get_install_logs,
+            mongodb_installer::set_cache_size_gb,
+            mongodb_installer::check_data_lock,
+            mongodb_installer::clear_stale_lock,
+            mongodb_installer::detect_package_manager,
+            mongodb_installer::enable_authentication,
+            mongodb_installer::detect_mongodb_source,
+            mongodb_installer::cancel_sudo_request,
+            mongodb_installer::check_external_accessibility,
+            mongodb_installer::check_for_upgrade,
+            mongodb_installer::upgrade_mongodb,
             
             // MongoDB database operations
             mongodb_manager::connect_mongodb,
             mongodb_manager::disconnect_mongodb,
             mongodb_manager::insert_document,
             mongodb_manager::find_documents,
+            mongodb_manager::find_advanced,
             mongodb_manager::update_document,
             mongodb_manager::delete_document,
+            mongodb_manager::update_many,
+            mongodb_manager::delete_many,
             mongodb_manager::list_collections,
+            mongodb_manager::gridfs_download,
+            mongodb_manager::cancel_gridfs_download,
+            mongodb_manager::import_csv,
+            mongodb_manager::read_oplog,
+            mongodb_manager::find_with_collation,
+            mongodb_manager::distinct_with_collation,
+            mongodb_manager::aggregate_with_collation,
+            mongodb_manager::benchmark_io,
+            mongodb_manager::create_partial_index,
+            mongodb_manager::parse_and_validate_json,
+            mongodb_manager::find_one_and_delete,
+            mongodb_manager::set_collection_prefix,
+            mongodb_manager::check_version_compatibility,
+            mongodb_manager::create_timeseries_collection,
+            mongodb_manager::update_across_collections,
+            mongodb_manager::aggregate_streamed,
+            mongodb_manager::validate_collection,
+            mongodb_manager::autoconnect_result,
+            mongodb_manager::duplicate_document,
+            mongodb_manager::current_connection_info,
+            mongodb_manager::index_build_progress,
+            mongodb_manager::create_index,
+            mongodb_manager::list_collections_detailed,
+            mongodb_manager::insert_document_with_concern,
+            mongodb_manager::infer_schema,
+            mongodb_manager::export_schema,
+            mongodb_manager::set_app_name,
+            mongodb_manager::truncate_collection,
+            mongodb_manager::import_json,
+            mongodb_manager::cancel_import,
+            mongodb_manager::sample_documents,
+            mongodb_manager::start_metrics_stream,
+            mongodb_manager::stop_metrics_stream,
+            mongodb_manager::connection_count,
+            mongodb_manager::reset_connection_pool,
+            mongodb_manager::find_explained,
+            mongodb_manager::check_reachable,
+            mongodb_manager::insert_encrypted,
+            mongodb_manager::find_decrypted,
+            mongodb_manager::cancel_aggregate,
+            mongodb_manager::diff_documents,
+            mongodb_manager::health_check_all,
+            mongodb_manager::find_one_by_field,
+            mongodb_manager::find_projected,
+            mongodb_manager::export_json,
+            mongodb_manager::export_csv,
+            mongodb_manager::get_runtime_config,
+            mongodb_manager::import_json_upsert,
+            mongodb_manager::find_cached,
+            mongodb_manager::invalidate_cache,
+            mongodb_manager::rotate_logs,
+            mongodb_manager::validate_against_schema,
+            mongodb_manager::list_users,
+            mongodb_manager::drop_user,
+            mongodb_manager::next_sequence,
+            mongodb_manager::export_parquet,
+            mongodb_manager::find_in_date_range,
+            mongodb_manager::watch_collection,
+            mongodb_manager::stop_watch,
+            mongodb_manager::get_resume_token,
+            mongodb_manager::aggregate_preview,
+            mongodb_manager::find_in_box,
+            mongodb_manager::backup_collections,
+            mongodb_manager::restore_collection,
+            mongodb_manager::list_background_tasks,
+            mongodb_manager::stop_task,
+            mongodb_manager::run_transaction,
+            mongodb_manager::save_profile,
+            mongodb_manager::list_profiles,
+            mongodb_manager::delete_profile,
+            mongodb_manager::connect_profile,
+            mongodb_manager::find_type_inconsistencies,
+            mongodb_manager::field_stats,
+            mongodb_manager::aggregate_merge,
+            mongodb_manager::snapshot_collections,
+            mongodb_manager::recent_topology_events,
+            mongodb_manager::update_if_unchanged,
+            mongodb_manager::lint_filter,
+            mongodb_manager::reauthenticate,
+            mongodb_manager::export_operation_log,
+            mongodb_manager::warm_collection,
+            mongodb_manager::find_after,
+            mongodb_manager::find_duplicates,
+            mongodb_manager::remove_duplicates,
+            mongodb_manager::check_index_coverage,
+            mongodb_manager::convert_field_type,
+            mongodb_manager::insert_many,
+            mongodb_manager::count_documents,
+            mongodb_manager::estimated_document_count,
+            mongodb_manager::aggregate,
+            mongodb_manager::find_one,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,200 @@
+// src/mongodb_installer/config.rs
+
+// Minimal editor for mongod's YAML config file. It only understands the
+// block-style mappings mongod.conf uses, which is enough to set nested scalar
+// keys such as `net.bindIp` while leaving comments and other settings intact.
+
+// Set a dotted key path (e.g. "net.bindIp") to a scalar value, creating any
+// missing sections at the end of their parent block
+pub fn set_value(contents: &str, key_path: &str, value: &str) -> String {
+    let mut lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let parts: Vec<&str> = key_path.split('.').collect();
+
+    // The block currently being searched: [start, end) and the indentation of its parent key
+    let mut start = 0;
+    let mut end = lines.len();
+    let mut parent_indent: Option<usize> = None;
+
+    for (depth, part) in parts.iter().enumerate() {
+        let is_leaf = depth == parts.len() - 1;
+        let child_indent = first_entry_indent(&lines[start..end])
+            .unwrap_or_else(|| parent_indent.map(|indent| indent + 2).unwrap_or(0));
+
+        match find_key(&lines, start, end, child_indent, part) {
+            Some(index) if is_leaf => {
+                // Keep any inline comment that followed the old value
+                let (_, comment) = split_inline_comment(&lines[index].trim_start()[part.len() + 1..]);
+                lines[index] = format!("{}{}: {}{}", " ".repeat(child_indent), part, value, comment);
+                break;
+            }
+            Some(index) => {
+                start = index + 1;
+                end = block_end(&lines, index, child_indent);
+                parent_indent = Some(child_indent);
+            }
+            None => {
+                // Append the remaining path as nested sections after the block's last entry
+                let insert_at = last_entry_end(&lines, start, end);
+                let mut new_lines = Vec::new();
+                for (offset, missing) in parts[depth..].iter().enumerate() {
+                    let indent = " ".repeat(child_indent + offset * 2);
+                    if depth + offset == parts.len() - 1 {
+                        new_lines.push(format!("{}{}: {}", indent, missing, value));
+                    } else {
+                        new_lines.push(format!("{}{}:", indent, missing));
+                    }
+                }
+                lines.splice(insert_at..insert_at, new_lines);
+                break;
+            }
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+// Read a dotted key path's scalar value, if present
+pub fn get_value(contents: &str, key_path: &str) -> Option<String> {
+    let lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+    let parts: Vec<&str> = key_path.split('.').collect();
+
+    let mut start = 0;
+    let mut end = lines.len();
+
+    for (depth, part) in parts.iter().enumerate() {
+        let child_indent = first_entry_indent(&lines[start..end])?;
+        let index = find_key(&lines, start, end, child_indent, part)?;
+
+        if depth == parts.len() - 1 {
+            let (value, _) = split_inline_comment(&lines[index].trim_start()[part.len() + 1..]);
+            let value = value.trim();
+            return Some(value.trim_matches('"').trim_matches('\'').to_string());
+        }
+
+        start = index + 1;
+        end = block_end(&lines, index, child_indent);
+    }
+
+    None
+}
+
+// Split the text after a key's colon into its value and any trailing ` # comment`,
+// keeping the comment's leading whitespace so alignment survives a rewrite
+fn split_inline_comment(rest: &str) -> (&str, &str) {
+    if rest.trim_start().starts_with('#') {
+        return ("", rest);
+    }
+    match rest.find(" #") {
+        Some(pos) => {
+            let value_end = rest[..pos].trim_end().len();
+            rest.split_at(value_end)
+        }
+        None => (rest, ""),
+    }
+}
+
+fn is_entry(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn first_entry_indent(lines: &[String]) -> Option<usize> {
+    lines.iter().find(|line| is_entry(line)).map(|line| indent_of(line))
+}
+
+fn find_key(lines: &[String], start: usize, end: usize, indent: usize, key: &str) -> Option<usize> {
+    (start..end).find(|&i| {
+        let line = &lines[i];
+        is_entry(line)
+            && indent_of(line) == indent
+            && line.trim_start().strip_prefix(key).is_some_and(|rest| rest.starts_with(':'))
+    })
+}
+
+// Index of the first entry after `index` that is not nested under it
+fn block_end(lines: &[String], index: usize, indent: usize) -> usize {
+    (index + 1..lines.len())
+        .find(|&i| is_entry(&lines[i]) && indent_of(&lines[i]) <= indent)
+        .unwrap_or(lines.len())
+}
+
+// Position just after the last real entry in [start, end), so inserts land before trailing comments
+fn last_entry_end(lines: &[String], start: usize, end: usize) -> usize {
+    (start..end)
+        .rev()
+        .find(|&i| is_entry(&lines[i]))
+        .map(|i| i + 1)
+        .unwrap_or(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONGOD_CONF: &str = "\
+# mongod.conf
+
+storage:
+  dbPath: /var/lib/mongodb
+
+net:
+  port: 27017
+  bindIp: 127.0.0.1  # local only
+  bindIpAll: false
+
+#security:
+
+# trailing notes
+";
+
+    #[test]
+    fn set_value_replaces_existing_nested_key() {
+        let updated = set_value(MONGOD_CONF, "net.port", "28017");
+        assert!(updated.contains("\n  port: 28017\n"));
+        assert!(!updated.contains("27017"));
+        assert_eq!(updated.lines().count(), MONGOD_CONF.lines().count());
+    }
+
+    #[test]
+    fn set_value_keeps_inline_comment() {
+        let updated = set_value(MONGOD_CONF, "net.bindIp", "0.0.0.0");
+        assert!(updated.contains("\n  bindIp: 0.0.0.0  # local only\n"));
+        assert!(updated.contains("\n  bindIpAll: false\n"));
+    }
+
+    #[test]
+    fn set_value_creates_missing_section_before_trailing_comments() {
+        let updated = set_value(MONGOD_CONF, "security.authorization", "enabled");
+        assert!(updated.contains("  bindIpAll: false\nsecurity:\n  authorization: enabled\n\n#security:"));
+        assert!(updated.ends_with("# trailing notes\n"));
+        assert_eq!(get_value(&updated, "security.authorization").as_deref(), Some("enabled"));
+    }
+
+    #[test]
+    fn set_value_fills_empty_parent_block() {
+        let contents = "net:\nstorage:\n  dbPath: /data\n";
+        let updated = set_value(contents, "net.bindIp", "0.0.0.0");
+        assert_eq!(updated, "net:\n  bindIp: 0.0.0.0\nstorage:\n  dbPath: /data\n");
+    }
+
+    #[test]
+    fn get_value_strips_inline_comment_and_quotes() {
+        assert_eq!(get_value(MONGOD_CONF, "net.bindIp").as_deref(), Some("127.0.0.1"));
+        assert_eq!(get_value("net:\n  bindIp: \"::1\" # ipv6\n", "net.bindIp").as_deref(), Some("::1"));
+        assert_eq!(get_value(MONGOD_CONF, "storage.dbPath").as_deref(), Some("/var/lib/mongodb"));
+    }
+
+    #[test]
+    fn get_value_does_not_match_key_prefix() {
+        let contents = "net:\n  bindIpAll: true\n";
+        assert_eq!(get_value(contents, "net.bindIp"), None);
+        assert_eq!(get_value(contents, "net.bindIpAll").as_deref(), Some("true"));
+        assert_eq!(get_value(MONGOD_CONF, "security.authorization"), None);
+    }
+}
@@ -1,19 +1,210 @@
 // src/mongodb_installer/mod.rs
 
 use tauri::AppHandle;
+use tauri::Emitter;
+use serde::{Serialize, Deserialize};
 use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
 // Import OS-specific modules
+mod config;
+mod sudo;
 mod ubuntu;
 mod windows;
 
-// Re-export shared types
-pub use ubuntu::SudoPasswordRequest;
+// Stable codes for `mongodb-install-error` events, the frontend branches on these
+pub const SUDO_REQUIRED: &str = "SUDO_REQUIRED";
+pub const PERMISSION_DENIED: &str = "PERMISSION_DENIED";
+pub const DISK_FULL: &str = "DISK_FULL";
+pub const DOWNLOAD_FAILED: &str = "DOWNLOAD_FAILED";
+pub const REPOSITORY_SETUP_FAILED: &str = "REPOSITORY_SETUP_FAILED";
+pub const PACKAGE_INSTALL_FAILED: &str = "PACKAGE_INSTALL_FAILED";
+pub const INSTALLER_FAILED: &str = "INSTALLER_FAILED";
+pub const DIRECTORY_CREATE_FAILED: &str = "DIRECTORY_CREATE_FAILED";
+pub const PATH_UPDATE_FAILED: &str = "PATH_UPDATE_FAILED";
+pub const SERVICE_START_FAILED: &str = "SERVICE_START_FAILED";
+pub const CONFIG_UPDATE_FAILED: &str = "CONFIG_UPDATE_FAILED";
+pub const COMMAND_FAILED: &str = "COMMAND_FAILED";
+pub const UNSUPPORTED_DISTRO: &str = "UNSUPPORTED_DISTRO";
+// Windows Installer results: a restart is needed to finish, or another MSI install is running
+pub const REBOOT_REQUIRED: &str = "REBOOT_REQUIRED";
+pub const INSTALL_IN_PROGRESS: &str = "INSTALL_IN_PROGRESS";
+// Non-fatal problems such as stderr output or a failed verification
+pub const INSTALL_WARNING: &str = "INSTALL_WARNING";
+
+// Newest MongoDB release the installer offers, and its release series
+pub(crate) const LATEST_MONGODB_VERSION: &str = "8.0.6";
+pub(crate) const LATEST_MONGODB_SERIES: &str = "8.0";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InstallError {
+    pub code: String,
+    pub message: String,
+    pub step: usize,
+    // Whether retrying the same operation can reasonably succeed
+    pub recoverable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InstallProgress {
+    step: usize,
+    total_steps: usize,
+    message: String,
+    is_error: bool,
+}
+
+// Install output is kept here as well as emitted, so a UI that mounts late (or reloads)
+// can backfill from `get_install_logs`. `dropped` keeps indexes stable once old entries fall off.
+const MAX_BUFFERED_LOGS: usize = 1000;
+
+struct InstallLogBuffer {
+    dropped: usize,
+    entries: VecDeque<InstallProgress>,
+}
+
+static INSTALL_LOGS: Mutex<InstallLogBuffer> = Mutex::new(InstallLogBuffer {
+    dropped: 0,
+    entries: VecDeque::new(),
+});
+
+fn buffer_install_log(progress: InstallProgress) {
+    let mut buffer = INSTALL_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    if buffer.entries.len() >= MAX_BUFFERED_LOGS {
+        buffer.entries.pop_front();
+        buffer.dropped += 1;
+    }
+    buffer.entries.push_back(progress);
+}
+
+// Clean raw command output for the log view: drop ANSI escape sequences (colors, cursor moves,
+// window titles) and other control characters, and resolve carriage returns the way a terminal
+// would, keeping only the text written after the last `\r` on each line
+pub(crate) fn sanitize_output(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameter and intermediate bytes, then one final byte
+                Some('[') => {
+                    chars.by_ref().find(|next| ('\u{40}'..='\u{7e}').contains(next));
+                }
+                // OSC: runs until BEL or ESC \
+                Some(']') => {
+                    while let Some(next) = chars.next() {
+                        if next == '\u{7}' || (next == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // nF escapes such as charset selection (ESC ( B): intermediate bytes, then a final byte
+                Some(next) if ('\u{20}'..='\u{2f}').contains(&next) => {
+                    chars.by_ref().find(|next| ('\u{30}'..='\u{7e}').contains(next));
+                }
+                // Any other escape is a single character
+                _ => {}
+            },
+            '\n' | '\r' | '\t' => stripped.push(c),
+            c if c.is_control() => {}
+            c => stripped.push(c),
+        }
+    }
+    
+    stripped
+        .split('\n')
+        .map(|line| {
+            let line = line.trim_end_matches('\r');
+            line.rsplit('\r').next().unwrap_or("")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Emit an install log line and record it for backfill
+pub(crate) fn emit_install_log(app: &AppHandle, step: usize, total_steps: usize, message: String) {
+    let progress = InstallProgress {
+        step,
+        total_steps,
+        message: sanitize_output(&message),
+        is_error: false,
+    };
+    
+    buffer_install_log(progress.clone());
+    app.emit("mongodb-install-log", progress).unwrap_or_default();
+}
+
+// Emit a structured installer error and hand the message back for the command's `Err`.
+// Output that clearly points at a full disk or a bad sudo password overrides a failure `code`.
+pub(crate) fn emit_install_error(app: &AppHandle, code: &str, step: usize, recoverable: bool, message: String) -> String {
+    let message = sanitize_output(&message);
+    let (code, recoverable) = resolve_error_code(code, recoverable, &message);
+    
+    let error = InstallError {
+        code: code.to_string(),
+        message: message.clone(),
+        step,
+        recoverable,
+    };
+    
+    buffer_install_log(InstallProgress {
+        step,
+        total_steps: 0,
+        message: format!("ERROR: {}", message),
+        is_error: true,
+    });
+    
+    println!("BACKEND ERROR [{}]: {}", code, message);
+    app.emit("mongodb-install-error", error).unwrap_or_default();
+    message
+}
+
+// Warnings keep their code, a stray "permission denied" in stderr is not a failure
+fn resolve_error_code<'a>(code: &'a str, recoverable: bool, message: &str) -> (&'a str, bool) {
+    if code == INSTALL_WARNING {
+        return (code, recoverable);
+    }
+    
+    match classify_failure(message) {
+        Some(DISK_FULL) => (DISK_FULL, false),
+        Some(detected) => (detected, recoverable),
+        None => (code, recoverable),
+    }
+}
+
+// Recognize failures whose cause is obvious from the command output
+fn classify_failure(output: &str) -> Option<&'static str> {
+    let output = output.to_lowercase();
+    
+    if output.contains("no space left on device")
+        || output.contains("enough free space")
+        || output.contains("not enough space on the disk")
+    {
+        Some(DISK_FULL)
+    } else if output.contains("incorrect password") || output.contains("a password is required") || output.contains("sorry, try again") {
+        Some(SUDO_REQUIRED)
+    } else if output.contains("permission denied") || output.contains("access is denied") {
+        Some(PERMISSION_DENIED)
+    } else {
+        None
+    }
+}
 
 #[tauri::command]
 pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
     let os = env::consts::OS;
     
+    // Each install starts with a fresh log, indexes restart at 0
+    {
+        let mut buffer = INSTALL_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.entries.clear();
+        buffer.dropped = 0;
+    }
+    
     match os {
         "linux" => ubuntu::install_mongodb(app).await,
         "windows" => windows::install_mongodb(&app).await,
@@ -21,6 +212,52 @@ pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
     }
 }
 
+// Cancel a pending sudo password request, e.g. when the user closes the password dialog, so
+// the install fails right away instead of waiting out the timeout. Returns false if the request
+// was already answered or timed out.
+#[tauri::command]
+pub async fn cancel_sudo_request(request_id: String) -> Result<bool, String> {
+    Ok(sudo::cancel_request(&request_id))
+}
+
+// Buffered log entries with an index of `since_index` or later, i.e. pass the number of
+// entries already seen. Entries older than the buffer's capacity are no longer available.
+#[tauri::command]
+pub async fn get_install_logs(since_index: usize) -> Result<Vec<InstallProgress>, String> {
+    let buffer = INSTALL_LOGS.lock().map_err(|e| format!("Install log buffer is unavailable: {}", e))?;
+    let skip = since_index.saturating_sub(buffer.dropped);
+    
+    Ok(buffer.entries.iter().skip(skip).cloned().collect())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlatformInfo {
+    pub os: String,
+    // Only set on Linux
+    pub distro: Option<ubuntu::LinuxDistro>,
+    pub package_manager: Option<String>,
+}
+
+// What install_mongodb will use on this machine, so the UI can explain up front when
+// the distribution isn't supported
+#[tauri::command]
+pub async fn detect_package_manager() -> Result<PlatformInfo, String> {
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => {
+            let distro = ubuntu::detect_distro()?;
+            Ok(PlatformInfo {
+                os: os.to_string(),
+                package_manager: Some(distro.package_manager().to_string()),
+                distro: Some(distro),
+            })
+        }
+        "windows" => Ok(PlatformInfo { os: os.to_string(), distro: None, package_manager: None }),
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
 #[tauri::command]
 pub async fn is_mongodb_installed() -> bool {
     let os = env::consts::OS;
@@ -30,4 +267,674 @@ pub async fn is_mongodb_installed() -> bool {
         "windows" => windows::is_mongodb_installed().await,
         _ => false, // Unsupported OS
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum MongoSource {
+    // Installed on this machine as a package or service
+    Native,
+    // A running Docker container from a mongo image
+    Docker,
+    // Something answers on the default port, but it isn't a local install or container
+    Remote,
+    NotFound,
+}
+
+const DEFAULT_MONGODB_PORT: u16 = 27017;
+const SOURCE_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+// Whether `docker ps` lists a running container started from a mongo image.
+// False when Docker isn't installed or its daemon isn't running.
+async fn mongo_container_running() -> bool {
+    let output = tokio::process::Command::new("docker")
+        .args(["ps", "--format", "{{.Image}}"])
+        .output();
+    
+    match tokio::time::timeout(SOURCE_PROBE_TIMEOUT, output).await {
+        Ok(Ok(output)) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|image| {
+                // Match "mongo", "mongo:7" and "bitnami/mongodb", but not unrelated images
+                let name = image.rsplit('/').next().unwrap_or(image);
+                name.split(':').next().is_some_and(|name| name == "mongo" || name == "mongodb")
+            }),
+        _ => false,
+    }
+}
+
+async fn default_port_reachable() -> bool {
+    let connect = tokio::net::TcpStream::connect(("127.0.0.1", DEFAULT_MONGODB_PORT));
+    matches!(tokio::time::timeout(SOURCE_PROBE_TIMEOUT, connect).await, Ok(Ok(_)))
+}
+
+// Poll the default port until mongod accepts connections, false if it didn't within `timeout`
+pub(crate) async fn wait_for_mongodb_ready(timeout: std::time::Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    
+    while tokio::time::Instant::now() < deadline {
+        if default_port_reachable().await {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    false
+}
+
+// Where a usable MongoDB comes from, so the app doesn't offer to install one when a
+// containerized server is already running
+#[tauri::command]
+pub async fn detect_mongodb_source() -> Result<MongoSource, String> {
+    if is_mongodb_installed().await {
+        return Ok(MongoSource::Native);
+    }
+    if mongo_container_running().await {
+        return Ok(MongoSource::Docker);
+    }
+    if default_port_reachable().await {
+        return Ok(MongoSource::Remote);
+    }
+    Ok(MongoSource::NotFound)
+}
+
+#[tauri::command]
+pub async fn list_installed_versions() -> Result<Vec<String>, String> {
+    let os = env::consts::OS;
+    
+    let mut versions = match os {
+        "linux" => ubuntu::list_installed_versions().await?,
+        "windows" => windows::list_installed_versions().await?,
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    };
+    
+    versions.sort_by_key(|v| version_key(v));
+    versions.dedup();
+    Ok(versions)
+}
+
+#[tauri::command]
+pub async fn select_active_version(app: AppHandle, version: String) -> Result<(), String> {
+    let installed = list_installed_versions().await?;
+    if !installed.contains(&version) {
+        return Err(format!(
+            "MongoDB version {} is not installed (installed versions: {})",
+            version,
+            if installed.is_empty() { "none".to_string() } else { installed.join(", ") }
+        ));
+    }
+    
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => ubuntu::select_active_version(app, &version).await,
+        "windows" => windows::select_active_version(&app, &version).await,
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
+// True for `localhost` and any IPv4/IPv6 loopback address (127.0.0.0/8, ::1)
+fn is_loopback_address(addr: &str) -> bool {
+    addr.eq_ignore_ascii_case("localhost")
+        || addr.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+#[tauri::command]
+pub async fn set_bind_ip(app: AppHandle, bind_ip: String, i_understand_security_risk: bool) -> Result<(), String> {
+    let addresses: Vec<&str> = bind_ip.split(',').map(|addr| addr.trim()).collect();
+    
+    if addresses.iter().any(|addr| {
+        addr.is_empty() || !addr.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '-' | '%'))
+    }) {
+        return Err(format!("Invalid bind IP list: {}", bind_ip));
+    }
+    
+    // Anything beyond loopback exposes the (possibly unauthenticated) server to the network
+    let exposes_network = addresses.iter().any(|addr| !is_loopback_address(addr));
+    if exposes_network {
+        if !i_understand_security_risk {
+            return Err(format!(
+                "Binding MongoDB to {} exposes it beyond this machine; pass i_understand_security_risk to confirm",
+                bind_ip
+            ));
+        }
+        
+        let warning = format!(
+            "WARNING: MongoDB will accept connections on {}. Anyone who can reach this machine can reach the database, enable authentication and firewall rules before sharing it.",
+            bind_ip
+        );
+        emit_install_log(&app, 0, 0, warning);
+    }
+    
+    let value = addresses.join(",");
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => ubuntu::update_mongod_config(app, &[("net.bindIp", value)], "Updating MongoDB bind IP").await,
+        "windows" => windows::update_mongod_config(&app, &[("net.bindIp", value)], "Updating MongoDB bind IP").await,
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FirewallStatus {
+    // No active firewall filters incoming connections
+    Disabled,
+    // A rule allows incoming TCP connections on the port
+    Allowed,
+    // A firewall is active and no rule allowing the port was found
+    Blocked,
+    // The rules couldn't be read, e.g. without root on Linux
+    Unknown { reason: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccessReport {
+    port: u16,
+    // Non-loopback addresses of this machine, i.e. what peers should connect to
+    addresses: Vec<String>,
+    // The addresses mongod accepted a connection on; empty means it only listens on loopback
+    listening_on: Vec<String>,
+    firewall: FirewallStatus,
+}
+
+// Whether a firewall port list such as "27017", "27000-28000", "80,27017" or "Any" covers `port`.
+// ufw writes ranges with ':' instead of '-'.
+fn port_list_contains(list: &str, port: u16) -> bool {
+    list.split(',').map(str::trim).any(|entry| {
+        if entry.eq_ignore_ascii_case("any") {
+            return true;
+        }
+        match entry.split_once(['-', ':']) {
+            Some((low, high)) => matches!(
+                (low.parse::<u16>(), high.parse::<u16>()),
+                (Ok(low), Ok(high)) if (low..=high).contains(&port)
+            ),
+            None => entry.parse() == Ok(port),
+        }
+    })
+}
+
+// Keep the addresses another machine could use, dropping loopback and link-local ones
+fn lan_addresses(addresses: Vec<String>) -> Vec<String> {
+    addresses
+        .into_iter()
+        .filter(|addr| match addr.parse::<std::net::IpAddr>() {
+            Ok(std::net::IpAddr::V4(ip)) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+            Ok(std::net::IpAddr::V6(ip)) => !ip.is_loopback() && !ip.is_unspecified() && (ip.segments()[0] & 0xffc0) != 0xfe80,
+            Err(_) => false,
+        })
+        .collect()
+}
+
+// After binding mongod beyond localhost (see set_bind_ip), report the addresses peers should
+// use, which of them mongod actually accepts connections on, and whether the firewall appears
+// to allow the port. The firewall check reads ufw/iptables on Linux and netsh on Windows; a
+// network firewall between the machines can still block the port.
+#[tauri::command]
+pub async fn check_external_accessibility(port: u16) -> Result<AccessReport, String> {
+    let os = env::consts::OS;
+    
+    let (addresses, firewall) = match os {
+        "linux" => (ubuntu::local_ip_addresses()?, ubuntu::firewall_status(port)),
+        "windows" => (windows::local_ip_addresses()?, windows::firewall_status(port)),
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    };
+    let addresses = lan_addresses(addresses);
+    
+    let mut listening_on = Vec::new();
+    for addr in &addresses {
+        let Ok(ip) = addr.parse::<std::net::IpAddr>() else { continue };
+        let connect = tokio::net::TcpStream::connect((ip, port));
+        if matches!(tokio::time::timeout(SOURCE_PROBE_TIMEOUT, connect).await, Ok(Ok(_))) {
+            listening_on.push(addr.clone());
+        }
+    }
+    
+    Ok(AccessReport { port, addresses, listening_on, firewall })
+}
+
+// Limit the WiredTiger cache, which by default takes about half of RAM. Useful when
+// mongod shares the machine with other memory-hungry applications.
+#[tauri::command]
+pub async fn set_cache_size_gb(app: AppHandle, size_gb: f64) -> Result<(), String> {
+    // mongod rejects cache sizes below 0.25 GB
+    if !size_gb.is_finite() || size_gb < 0.25 {
+        return Err(format!("Cache size must be at least 0.25 GB, got {}", size_gb));
+    }
+    
+    let os = env::consts::OS;
+    
+    let total_memory = match os {
+        "linux" => ubuntu::total_memory_bytes()?,
+        "windows" => windows::total_memory_bytes()?,
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    };
+    let total_gb = total_memory as f64 / (1024.0 * 1024.0 * 1024.0);
+    if size_gb > total_gb {
+        return Err(format!(
+            "Cache size of {} GB is larger than this machine's {:.2} GB of physical memory",
+            size_gb, total_gb
+        ));
+    }
+    
+    let setting = [("storage.wiredTiger.engineConfig.cacheSizeGB", size_gb.to_string())];
+    
+    match os {
+        "linux" => ubuntu::update_mongod_config(app, &setting, "Updating WiredTiger cache size").await,
+        "windows" => windows::update_mongod_config(&app, &setting, "Updating WiredTiger cache size").await,
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
+// On-disk footprint of the mongod data directory, including journal and WiredTiger
+// overhead, as opposed to the logical sizes reported by dbStats
+#[tauri::command]
+pub async fn data_directory_size() -> Result<u64, String> {
+    let os = env::consts::OS;
+    
+    let data_dir = match os {
+        "linux" => ubuntu::data_directory(),
+        "windows" => windows::data_directory(),
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    };
+    
+    tauri::async_runtime::spawn_blocking(move || directory_size(&data_dir))
+        .await
+        .map_err(|e| format!("Failed to measure data directory: {}", e))?
+}
+
+fn directory_size(path: &Path) -> Result<u64, String> {
+    let describe = |e: std::io::Error| match e.kind() {
+        ErrorKind::PermissionDenied => format!(
+            "Permission denied reading {} (the data directory is usually owned by the mongodb service account)",
+            path.display()
+        ),
+        ErrorKind::NotFound => format!("Data directory not found: {}", path.display()),
+        _ => format!("Failed to read {}: {}", path.display(), e),
+    };
+    
+    // symlink_metadata so links are counted as themselves rather than followed
+    let metadata = fs::symlink_metadata(path).map_err(describe)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    
+    let mut total = 0;
+    for entry in fs::read_dir(path).map_err(describe)? {
+        let entry = entry.map_err(describe)?;
+        total += directory_size(&entry.path())?;
+    }
+    
+    Ok(total)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LockStatus {
+    // mongod.lock exists and is non-empty, a clean shutdown leaves it empty
+    pub locked: bool,
+    // Locked, but the owning mongod is no longer running
+    pub stale: bool,
+    pub pid: Option<i32>,
+}
+
+fn data_lock_path() -> Result<std::path::PathBuf, String> {
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => Ok(ubuntu::data_directory().join("mongod.lock")),
+        "windows" => Ok(windows::data_directory().join("mongod.lock")),
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
+fn inspect_data_lock(lock_path: &Path) -> Result<LockStatus, String> {
+    let size = match fs::metadata(lock_path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == ErrorKind::NotFound => 0,
+        Err(e) => return Err(format!("Failed to inspect {}: {}", lock_path.display(), e)),
+    };
+    if size == 0 {
+        return Ok(LockStatus { locked: false, stale: false, pid: None });
+    }
+    
+    // The lock is usually readable only by the service account; without the pid,
+    // fall back to checking whether any mongod is running
+    let pid = fs::read_to_string(lock_path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<i32>().ok());
+    
+    let alive = match env::consts::OS {
+        "windows" => windows::mongod_process_alive(pid),
+        _ => ubuntu::mongod_process_alive(pid),
+    };
+    
+    Ok(LockStatus { locked: true, stale: !alive, pid })
+}
+
+// Look for a mongod.lock left behind by a crashed mongod, which stops the service from starting
+#[tauri::command]
+pub async fn check_data_lock(app: AppHandle) -> Result<LockStatus, String> {
+    let lock_path = data_lock_path()?;
+    let status = inspect_data_lock(&lock_path)?;
+    
+    if status.stale {
+        emit_install_log(&app, 0, 0, format!(
+            "Found a stale lock at {}, mongod will not start until it is cleared",
+            lock_path.display()
+        ));
+    }
+    
+    Ok(status)
+}
+
+// Remove mongod.lock, but only after re-checking that no mongod owns it
+#[tauri::command]
+pub async fn clear_stale_lock(app: AppHandle) -> Result<(), String> {
+    let lock_path = data_lock_path()?;
+    let status = inspect_data_lock(&lock_path)?;
+    
+    if !status.locked {
+        return Ok(());
+    }
+    if !status.stale {
+        return Err(format!(
+            "mongod.lock is held by a running mongod{}, stop it instead of removing the lock",
+            status.pid.map(|pid| format!(" (pid {})", pid)).unwrap_or_default()
+        ));
+    }
+    
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => ubuntu::remove_lock_file(&app, &lock_path).await?,
+        "windows" => windows::remove_lock_file(&app, &lock_path).await?,
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    }
+    
+    emit_install_log(&app, 0, 0, format!("Removed stale lock {}", lock_path.display()));
+    Ok(())
+}
+
+// The locally installed mongod, which enable_authentication talks to directly
+const LOCAL_MONGODB_URI: &str = "mongodb://127.0.0.1:27017";
+const MIN_ADMIN_PASSWORD_LEN: usize = 12;
+
+fn validate_admin_credentials(admin_user: &str, admin_password: &str) -> Result<(), String> {
+    if admin_user.trim().is_empty() {
+        return Err("Admin user name must not be empty".to_string());
+    }
+    if admin_password.len() < MIN_ADMIN_PASSWORD_LEN {
+        return Err(format!("Admin password must be at least {} characters", MIN_ADMIN_PASSWORD_LEN));
+    }
+    if admin_password.to_lowercase().contains(&admin_user.to_lowercase()) {
+        return Err("Admin password must not contain the user name".to_string());
+    }
+    if !admin_password.chars().any(|c| c.is_alphabetic()) || !admin_password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Admin password must contain both letters and digits".to_string());
+    }
+    Ok(())
+}
+
+async fn local_admin_database(credential: Option<mongodb::options::Credential>) -> Result<mongodb::Database, String> {
+    let mut options = mongodb::options::ClientOptions::parse(LOCAL_MONGODB_URI)
+        .await
+        .map_err(|e| format!("Failed to parse local connection string: {}", e))?;
+    options.server_selection_timeout = Some(std::time::Duration::from_secs(5));
+    options.credential = credential;
+    
+    let client = mongodb::Client::with_options(options)
+        .map_err(|e| format!("Failed to create MongoDB client: {}", e))?;
+    Ok(client.database("admin"))
+}
+
+// Create a `root` admin user on the local server and turn on `security.authorization`.
+// This is one-way from the app's point of view: afterwards every connection, including the
+// app's own, needs these credentials, and turning auth back off means editing mongod.conf by hand.
+#[tauri::command]
+pub async fn enable_authentication(app: AppHandle, admin_user: String, admin_password: String) -> Result<(), String> {
+    validate_admin_credentials(&admin_user, &admin_password)?;
+    let total_steps = 3;
+    
+    // The user has to exist before auth is enabled, or nobody could log in to create it
+    emit_install_log(&app, 1, total_steps, format!("Creating admin user '{}'", admin_user));
+    let admin = local_admin_database(None)
+        .await
+        .map_err(|e| emit_install_error(&app, COMMAND_FAILED, 1, true, e))?;
+    let create_user = mongodb::bson::doc! {
+        "createUser": &admin_user,
+        "pwd": &admin_password,
+        "roles": [{ "role": "root", "db": "admin" }],
+    };
+    
+    if let Err(e) = admin.run_command(create_user, None).await {
+        let message = e.to_string();
+        if message.contains("requires authentication") || message.contains("not authorized") {
+            return Err(emit_install_error(&app, COMMAND_FAILED, 1, false, "Authentication is already enabled on this server".to_string()));
+        }
+        if !message.contains("already exists") {
+            return Err(emit_install_error(&app, COMMAND_FAILED, 1, true, format!("Failed to create admin user: {}", message)));
+        }
+        
+        // Left over from an earlier attempt that failed later on; only carry on if the password matches
+        let credential = mongodb::options::Credential::builder()
+            .username(admin_user.clone())
+            .password(admin_password.clone())
+            .source("admin".to_string())
+            .build();
+        let authenticated = local_admin_database(Some(credential)).await?
+            .run_command(mongodb::bson::doc! { "ping": 1 }, None)
+            .await;
+        if authenticated.is_err() {
+            return Err(emit_install_error(&app, COMMAND_FAILED, 1, false, format!(
+                "User '{}' already exists with a different password", admin_user
+            )));
+        }
+        emit_install_log(&app, 1, total_steps, format!("Admin user '{}' already exists, reusing it", admin_user));
+    }
+    
+    emit_install_log(&app, 2, total_steps, "Enabling authorization in the mongod config".to_string());
+    let setting = [("security.authorization", "enabled".to_string())];
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => ubuntu::update_mongod_config(app.clone(), &setting, "Enabling MongoDB authentication").await?,
+        "windows" => windows::update_mongod_config(&app, &setting, "Enabling MongoDB authentication").await?,
+        _ => return Err(format!("Unsupported operating system: {}", os)),
+    }
+    
+    emit_install_log(&app, total_steps, total_steps, format!(
+        "Authentication enabled. Reconnect as '{}' (authSource=admin) to keep using the database",
+        admin_user
+    ));
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpgradeInfo {
+    // Newest installed version, None when MongoDB isn't installed. On Windows this is a
+    // release series such as "7.0", which is how the server directories are named.
+    installed: Option<String>,
+    latest: String,
+    upgrade_available: bool,
+    // Data compatibility notes (or why the upgrade isn't possible yet) for that upgrade
+    warning: Option<String>,
+}
+
+// "major.minor" of a version, e.g. "7.0" for "7.0.14"
+fn release_series(version: &str) -> String {
+    let key = version_key(version);
+    format!("{}.{}", key.first().copied().unwrap_or(0), key.get(1).copied().unwrap_or(0))
+}
+
+// Whether `target` is newer than `installed`, comparing only as many parts as `installed` has
+fn is_newer_version(target: &str, installed: &str) -> bool {
+    let installed_key = version_key(installed);
+    let mut target_key = version_key(target);
+    target_key.truncate(installed_key.len());
+    target_key > installed_key
+}
+
+// MongoDB only supports upgrading one major release at a time, and the new binaries refuse to
+// start on data whose featureCompatibilityVersion (FCV) is older than the release before them
+fn upgrade_warning(installed: &str, target: &str) -> Result<Option<String>, String> {
+    let (from, to) = (version_key(installed), version_key(target));
+    let (from_major, to_major) = (from.first().copied().unwrap_or(0), to.first().copied().unwrap_or(0));
+    
+    if to_major > from_major + 1 {
+        return Err(format!(
+            "MongoDB must be upgraded one major release at a time, upgrade {} to {}.0 first",
+            installed, from_major + 1
+        ));
+    }
+    if release_series(installed) == release_series(target) {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "Upgrading from {from} to {to} changes the data format. featureCompatibilityVersion must be \"{from}\" before upgrading. \
+         Once the upgrade is verified, raise it with setFeatureCompatibilityVersion: \"{to}\", after which {from} can no longer read the data. \
+         Back up the data directory first.",
+        from = release_series(installed),
+        to = release_series(target),
+    )))
+}
+
+// featureCompatibilityVersion of the local server, None if it isn't reachable or needs auth
+async fn local_feature_compatibility_version() -> Option<String> {
+    let admin = local_admin_database(None).await.ok()?;
+    let command = mongodb::bson::doc! { "getParameter": 1, "featureCompatibilityVersion": 1 };
+    let reply = admin.run_command(command, None).await.ok()?;
+    
+    reply.get_document("featureCompatibilityVersion").ok()?
+        .get_str("version").ok()
+        .map(|version| version.to_string())
+}
+
+// The version upgrade_mongodb upgrades from. On Linux that's the apt-managed install, since the
+// upgrade moves its repository and packages; tarball installs in /opt/mongodb are left alone.
+async fn upgradable_version() -> Result<Option<String>, String> {
+    match env::consts::OS {
+        "linux" => Ok(ubuntu::system_mongod_version()),
+        _ => Ok(list_installed_versions().await?.pop()),
+    }
+}
+
+// Compare the installed MongoDB with the newest release the installer offers
+#[tauri::command]
+pub async fn check_for_upgrade() -> Result<UpgradeInfo, String> {
+    let installed = upgradable_version().await?;
+    let (upgrade_available, warning) = match &installed {
+        Some(installed) if is_newer_version(LATEST_MONGODB_VERSION, installed) => {
+            (true, upgrade_warning(installed, LATEST_MONGODB_VERSION).unwrap_or_else(Some))
+        }
+        _ => (false, None),
+    };
+    
+    Ok(UpgradeInfo {
+        installed,
+        latest: LATEST_MONGODB_VERSION.to_string(),
+        upgrade_available,
+        warning,
+    })
+}
+
+// Upgrade the installed MongoDB to `target_version` (e.g. "8.0.6"). On Linux the apt repository
+// is moved to the target's series and the packages are installed at that version; on Windows
+// the target's MSI is installed and the service switched to it. Crossing a release series
+// requires the current featureCompatibilityVersion, which is checked when the local server
+// is reachable without authentication.
+#[tauri::command]
+pub async fn upgrade_mongodb(app: AppHandle, target_version: String) -> Result<(), String> {
+    let key = version_key(&target_version);
+    if key.len() != 3 || !target_version.split('.').all(|part| part.parse::<u64>().is_ok()) {
+        return Err(format!("Invalid target version '{}', expected e.g. \"8.0.6\"", target_version));
+    }
+    
+    let installed = upgradable_version().await?
+        .ok_or_else(|| "MongoDB is not installed, install it instead of upgrading".to_string())?;
+    if !is_newer_version(&target_version, &installed) {
+        return Err(format!("MongoDB {} is already installed, which is not older than {}", installed, target_version));
+    }
+    
+    let installed_series = release_series(&installed);
+    let target_series = release_series(&target_version);
+    if let Some(warning) = upgrade_warning(&installed, &target_version)? {
+        match local_feature_compatibility_version().await {
+            Some(fcv) if version_key(&fcv) < version_key(&installed_series) => {
+                return Err(emit_install_error(&app, COMMAND_FAILED, 0, false, format!(
+                    "featureCompatibilityVersion is {}, set it to {} before upgrading to {}",
+                    fcv, installed_series, target_version
+                )));
+            }
+            Some(_) => {}
+            None => emit_install_log(&app, 0, 0, format!(
+                "WARNING: Could not read featureCompatibilityVersion, make sure it is \"{}\"",
+                installed_series
+            )),
+        }
+        emit_install_log(&app, 0, 0, format!("WARNING: {}", warning));
+    }
+    
+    let os = env::consts::OS;
+    
+    match os {
+        "linux" => ubuntu::upgrade_mongodb(app, &installed_series, &target_series, &target_version).await,
+        "windows" => windows::upgrade_mongodb(&app, &target_series, &target_version).await,
+        _ => Err(format!("Unsupported operating system: {}", os)),
+    }
+}
+
+// Numeric sort key for version strings like "8.0.6", non-numeric parts count as 0
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse::<u64>().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn resolve_error_code_only_reclassifies_failures() {
+        assert_eq!(resolve_error_code(COMMAND_FAILED, true, "mkdir: Permission denied"), (PERMISSION_DENIED, true));
+        assert_eq!(resolve_error_code(COMMAND_FAILED, true, "write error: No space left on device"), (DISK_FULL, false));
+        assert_eq!(resolve_error_code(COMMAND_FAILED, true, "exit status 1"), (COMMAND_FAILED, true));
+        assert_eq!(resolve_error_code(INSTALL_WARNING, true, "mkdir: Permission denied"), (INSTALL_WARNING, true));
+        assert_eq!(resolve_error_code(INSTALL_WARNING, true, "No space left on device"), (INSTALL_WARNING, true));
+    }
+    
+    #[test]
+    fn is_loopback_address_accepts_only_loopback() {
+        for addr in ["127.0.0.1", "127.0.1.1", "::1", "localhost", "LocalHost"] {
+            assert!(is_loopback_address(addr), "{}", addr);
+        }
+        for addr in ["0.0.0.0", "::", "192.168.1.10", "fe80::1", "example.com"] {
+            assert!(!is_loopback_address(addr), "{}", addr);
+        }
+        assert!("127.0.0.1,::1".split(',').all(is_loopback_address));
+    }
+    
+    #[test]
+    fn sanitize_output_strips_ansi_sequences() {
+        assert_eq!(sanitize_output("\u{1b}[32mSUCCESS\u{1b}[0m: installed"), "SUCCESS: installed");
+        assert_eq!(sanitize_output("\u{1b}[1;31;40mError\u{1b}[K"), "Error");
+        assert_eq!(sanitize_output("\u{1b}]0;Windows PowerShell\u{7}Ready"), "Ready");
+        assert_eq!(sanitize_output("\u{1b}]2;title\u{1b}\\Ready"), "Ready");
+        assert_eq!(sanitize_output("\u{1b}(BPlain"), "Plain");
+    }
+    
+    #[test]
+    fn sanitize_output_resolves_carriage_returns() {
+        assert_eq!(sanitize_output("Downloading 10%\rDownloading 55%\rDownloading 100%"), "Downloading 100%");
+        assert_eq!(sanitize_output("first line\r\nsecond line\r\n"), "first line\nsecond line\n");
+        assert_eq!(sanitize_output("\u{1b}[2K\r\u{1b}[36mProgress\u{1b}[0m 3/5\r"), "Progress 3/5");
+    }
+    
+    #[test]
+    fn sanitize_output_drops_other_control_characters() {
+        assert_eq!(sanitize_output("bell\u{7} and back\u{8}space\tkept"), "bell and backspace\tkept");
+        assert_eq!(sanitize_output("plain output"), "plain output");
+    }
+}
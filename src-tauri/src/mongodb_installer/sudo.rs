@@ -0,0 +1,79 @@
+// src/mongodb_installer/sudo.rs
+
+// The single place the installer asks the frontend for a sudo password. The
+// request is emitted with a unique id and the frontend answers on
+// `sudo-password-response-<id>`, or cancels it with `cancel_sudo_request`.
+
+use tauri::AppHandle;
+use tauri::Emitter;
+use tauri::Listener;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+const SUDO_PASSWORD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SudoPasswordRequest {
+    pub request_id: String,
+}
+
+enum SudoResponse {
+    Password(String),
+    Cancelled,
+}
+
+// Requests waiting for the frontend, keyed by request id
+static PENDING_REQUESTS: Mutex<BTreeMap<String, oneshot::Sender<SudoResponse>>> = Mutex::new(BTreeMap::new());
+
+fn take_pending(request_id: &str) -> Option<oneshot::Sender<SudoResponse>> {
+    PENDING_REQUESTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(request_id)
+}
+
+// Answer a pending request as cancelled. Returns false if it was already answered or timed out.
+pub(crate) fn cancel_request(request_id: &str) -> bool {
+    take_pending(request_id).is_some_and(|sender| sender.send(SudoResponse::Cancelled).is_ok())
+}
+
+pub(crate) async fn get_sudo_password(app: &AppHandle) -> Result<String, anyhow::Error> {
+    let (tx, rx) = oneshot::channel();
+    let request_id = Uuid::new_v4().to_string();
+
+    println!("Requesting sudo password with request_id: {}", request_id);
+
+    PENDING_REQUESTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(request_id.clone(), tx);
+
+    let event_name = format!("sudo-password-response-{}", request_id);
+    let handler = {
+        let request_id = request_id.clone();
+        app.listen(event_name, move |event| {
+            let password = serde_json::from_str(event.payload())
+                .unwrap_or_default();
+
+            if let Some(sender) = take_pending(&request_id) {
+                let _ = sender.send(SudoResponse::Password(password));
+            }
+        })
+    };
+
+    let emitted = app.emit("sudo-password-request", SudoPasswordRequest {
+        request_id: request_id.clone()
+    });
+    let response = match emitted {
+        Ok(()) => tokio::time::timeout(SUDO_PASSWORD_TIMEOUT, rx).await,
+        Err(e) => {
+            app.unlisten(handler);
+            take_pending(&request_id);
+            return Err(e.into());
+        }
+    };
+
+    app.unlisten(handler);
+    take_pending(&request_id);
+    match response?? {
+        SudoResponse::Password(password) => Ok(password),
+        SudoResponse::Cancelled => Err(anyhow::anyhow!("Sudo password request was cancelled by user")),
+    }
+}
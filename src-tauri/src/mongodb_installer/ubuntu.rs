@@ -1,97 +1,244 @@
 // src/mongodb_installer/ubuntu.rs
 
 use tauri::AppHandle;
+use tauri::Emitter;
 use tauri_plugin_shell::process::CommandEvent;
 use anyhow::Result;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use std::sync::Arc;
-use tokio::sync::oneshot;
-use tauri::Listener;
-use tauri::Emitter;
+use std::fs;
+use std::path::Path;
 use tauri_plugin_shell::ShellExt;
+use super::{config, LATEST_MONGODB_SERIES};
+use super::{port_list_contains, FirewallStatus};
+use super::sudo::get_sudo_password;
+use super::{
+    emit_install_error, emit_install_log, COMMAND_FAILED, CONFIG_UPDATE_FAILED, DOWNLOAD_FAILED, INSTALL_WARNING, PACKAGE_INSTALL_FAILED,
+    REPOSITORY_SETUP_FAILED, SERVICE_START_FAILED, SUDO_REQUIRED, UNSUPPORTED_DISTRO,
+};
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct SudoPasswordRequest {
-    pub request_id: String,
+// Tarball installs are expected under /opt/mongodb/<version>, the apt package provides one system version
+const TARBALL_INSTALL_DIR: &str = "/opt/mongodb";
+const SERVICE_OVERRIDE_DIR: &str = "/etc/systemd/system/mongod.service.d";
+const MONGOD_CONFIG_PATH: &str = "/etc/mongod.conf";
+// Where the mongodb-org-server package installs mongod. Tarball symlinks in /usr/local/bin
+// shadow it on PATH while another version is active.
+const PACKAGED_MONGOD_PATH: &str = "/usr/bin/mongod";
+
+// Releases with a MongoDB apt repository for the latest series (LATEST_MONGODB_SERIES)
+const SUPPORTED_UBUNTU_CODENAMES: [&str; 3] = ["focal", "jammy", "noble"];
+const SUPPORTED_DEBIAN_CODENAMES: [&str; 1] = ["bookworm"];
+
+// Fields from /etc/os-release used to pick the package manager and MongoDB repository
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LinuxDistro {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub version_id: String,
+    pub version_codename: String,
+    // Set by Ubuntu derivatives such as Linux Mint to the Ubuntu release they're based on
+    pub ubuntu_codename: String,
 }
 
-async fn get_sudo_password(app: &AppHandle) -> Result<String, anyhow::Error> {
-    let (tx, rx) = oneshot::channel();
-    let tx = Arc::new(tokio::sync::Mutex::new(Some(tx)));
-    let request_id = Uuid::new_v4().to_string();
+impl LinuxDistro {
+    fn is(&self, family: &str) -> bool {
+        self.id == family || self.id_like.iter().any(|id| id == family)
+    }
 
-    println!("Requesting sudo password with request_id: {}", request_id);
+    pub fn package_manager(&self) -> &'static str {
+        if self.is("ubuntu") || self.is("debian") {
+            "apt"
+        } else if self.is("rhel") || self.is("centos") || self.is("fedora") {
+            let has_dnf = std::process::Command::new("which")
+                .arg("dnf")
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+            if has_dnf { "dnf" } else { "yum" }
+        } else if self.is("arch") {
+            "pacman"
+        } else {
+            "unknown"
+        }
+    }
+}
 
-    app.emit("sudo-password-request", SudoPasswordRequest {
-        request_id: request_id.clone()
-    })?;
+pub fn detect_distro() -> Result<LinuxDistro, String> {
+    let contents = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .map_err(|e| format!("Could not identify the Linux distribution (no os-release file): {}", e))?;
+    
+    let mut distro = LinuxDistro::default();
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim_matches('\'').to_string();
+        
+        match key.trim() {
+            "ID" => distro.id = value,
+            "ID_LIKE" => distro.id_like = value.split_whitespace().map(|id| id.to_string()).collect(),
+            "PRETTY_NAME" => distro.name = value,
+            "NAME" if distro.name.is_empty() => distro.name = value,
+            "VERSION_ID" => distro.version_id = value,
+            "VERSION_CODENAME" => distro.version_codename = value,
+            "UBUNTU_CODENAME" => distro.ubuntu_codename = value,
+            _ => {}
+        }
+    }
+    if distro.name.is_empty() {
+        distro.name = distro.id.clone();
+    }
+    
+    Ok(distro)
+}
 
-    let event_name = format!("sudo-password-response-{}", request_id);
-    let handler = app.listen(event_name, move |event| {
-        let tx = tx.clone();
-        tauri::async_runtime::spawn(async move {
-            let password = serde_json::from_str(event.payload())
-                .unwrap_or_default();
+// (command, description, error code) for each install step
+type InstallStep = (String, &'static str, &'static str);
 
-            let mut guard = tx.lock().await;
-            if let Some(sender) = guard.take() {
-                let _ = sender.send(password);
-            }
-        });
-    });
+fn install_steps(distro: &LinuxDistro) -> Result<Vec<InstallStep>, String> {
+    match distro.package_manager() {
+        "apt" => apt_install_steps(distro),
+        package_manager @ ("dnf" | "yum") => rpm_install_steps(distro, package_manager),
+        "pacman" => Err(format!(
+            "{} is not supported yet: MongoDB is not in the Arch repositories (only the AUR), so pacman cannot install it",
+            distro.name
+        )),
+        _ => Err(format!("Unsupported Linux distribution: {} (id \"{}\")", distro.name, distro.id)),
+    }
+}
 
-    let password = tokio::time::timeout(
-        std::time::Duration::from_secs(120),
-        rx
-    ).await??;
+// Makes apt-get print machine-readable progress on stdout, see apt_status_percentage
+const APT_STATUS_OPTION: &str = "-o APT::Status-Fd=1";
 
-    app.unlisten(handler);
-    Ok(password)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InstallPercentage {
+    step: usize,
+    total_steps: usize,
+    // Progress through the current step
+    step_percentage: f64,
+    // Progress through the whole install, counting each step as an equal slice
+    percentage: f64,
 }
 
-pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
-    let password = get_sudo_password(&app).await.map_err(|e| e.to_string())?;
+// Progress through an apt-get install from its APT::Status-Fd lines, e.g.
+// "dlstatus:3:45.2:Retrieving file 3 of 7" or "pmstatus:mongodb-org-server:60:Unpacking ...".
+// Downloading counts as the first half of the install and unpacking/configuring as the second.
+fn apt_status_percentage(line: &str) -> Option<f64> {
+    let mut fields = line.trim().splitn(4, ':');
+    let kind = fields.next()?;
+    let percentage: f64 = fields.nth(1)?.parse().ok()?;
+    let percentage = percentage.clamp(0.0, 100.0);
+    
+    match kind {
+        "dlstatus" => Some(percentage / 2.0),
+        "pmstatus" => Some(50.0 + percentage / 2.0),
+        _ => None,
+    }
+}
 
-    // MongoDB 8.0 installation commands for Ubuntu 24.04 (Noble) - without system-wide upgrade
-    let commands = [
-        // Step 1: Update package database only (no upgrade)
-        "apt-get update",
-        
-        // Step 2: Install required dependencies
-        "apt-get install -y gnupg curl",
-        
-        // Step 3: Import MongoDB public GPG key (force overwrite)
-        "curl -fsSL https://www.mongodb.org/static/pgp/server-8.0.asc | gpg --yes -o /usr/share/keyrings/mongodb-server-8.0.gpg --dearmor",
-        
-        // Step 4: Add MongoDB repository
-        "echo \"deb [ arch=amd64,arm64 signed-by=/usr/share/keyrings/mongodb-server-8.0.gpg ] https://repo.mongodb.org/apt/ubuntu noble/mongodb-org/8.0 multiverse\" | tee /etc/apt/sources.list.d/mongodb-org-8.0.list",
-        
-        // Step 5: Reload package database for MongoDB repo only
-        "apt-get update -o Dir::Etc::sourcelist=\"sources.list.d/mongodb-org-8.0.list\" -o Dir::Etc::sourceparts=\"-\" -o APT::Get::List-Cleanup=\"0\"",
-        
-        // Step 6: Install MongoDB packages only
-        "DEBIAN_FRONTEND=noninteractive apt-get install -y mongodb-org",
-        
-        // Step 7: Enable and start MongoDB service
-        "systemctl daemon-reload && systemctl enable mongod && systemctl start mongod"
+// The latest MongoDB series from the official apt repository, without a system-wide upgrade
+fn apt_install_steps(distro: &LinuxDistro) -> Result<Vec<InstallStep>, String> {
+    let mut steps = vec![
+        // Update package database only (no upgrade)
+        ("apt-get update".to_string(), "Updating package database", PACKAGE_INSTALL_FAILED),
+        ("apt-get install -y gnupg curl".to_string(), "Installing dependencies", PACKAGE_INSTALL_FAILED),
     ];
+    steps.extend(apt_repository_steps(distro, LATEST_MONGODB_SERIES)?);
+    steps.push((format!("DEBIAN_FRONTEND=noninteractive apt-get {} install -y mongodb-org", APT_STATUS_OPTION), "Installing MongoDB packages", PACKAGE_INSTALL_FAILED));
+    steps.push(("systemctl daemon-reload && systemctl enable mongod && systemctl start mongod".to_string(), "Starting MongoDB service", SERVICE_START_FAILED));
+    Ok(steps)
+}
+
+// Add the apt repository for a MongoDB release series such as "8.0" and refresh its package list
+fn apt_repository_steps(distro: &LinuxDistro, series: &str) -> Result<Vec<InstallStep>, String> {
+    let (repo, codename) = if distro.is("ubuntu") {
+        let codename = if distro.ubuntu_codename.is_empty() { &distro.version_codename } else { &distro.ubuntu_codename };
+        if !SUPPORTED_UBUNTU_CODENAMES.contains(&codename.as_str()) {
+            return Err(format!("{} (Ubuntu {}) has no MongoDB {} packages, supported releases: {}", distro.name, codename, series, SUPPORTED_UBUNTU_CODENAMES.join(", ")));
+        }
+        (format!("https://repo.mongodb.org/apt/ubuntu {}/mongodb-org/{} multiverse", codename, series), codename)
+    } else {
+        let codename = &distro.version_codename;
+        if !SUPPORTED_DEBIAN_CODENAMES.contains(&codename.as_str()) {
+            return Err(format!("{} (Debian {}) has no MongoDB {} packages, supported releases: {}", distro.name, codename, series, SUPPORTED_DEBIAN_CODENAMES.join(", ")));
+        }
+        (format!("https://repo.mongodb.org/apt/debian {}/mongodb-org/{} main", codename, series), codename)
+    };
+    println!("Using MongoDB {} apt repository for {}", series, codename);
+    
+    Ok(vec![
+        // Import MongoDB public GPG key (force overwrite)
+        (
+            format!("curl -fsSL https://www.mongodb.org/static/pgp/server-{0}.asc | gpg --yes -o /usr/share/keyrings/mongodb-server-{0}.gpg --dearmor", series),
+            "Importing MongoDB GPG key",
+            DOWNLOAD_FAILED,
+        ),
+        (
+            format!("echo \"deb [ arch=amd64,arm64 signed-by=/usr/share/keyrings/mongodb-server-{}.gpg ] {}\" | tee /etc/apt/sources.list.d/mongodb-org-{}.list", series, repo, series),
+            "Adding MongoDB repository",
+            REPOSITORY_SETUP_FAILED,
+        ),
+        // Reload package database for MongoDB repo only
+        (
+            format!("apt-get update -o Dir::Etc::sourcelist=\"sources.list.d/mongodb-org-{}.list\" -o Dir::Etc::sourceparts=\"-\" -o APT::Get::List-Cleanup=\"0\"", series),
+            "Updating MongoDB package database",
+            REPOSITORY_SETUP_FAILED,
+        ),
+    ])
+}
+
+// The latest MongoDB series from the official yum repository for RHEL and its rebuilds. Fedora has no
+// repository of its own, the RHEL 9 packages are the closest match.
+fn rpm_install_steps(distro: &LinuxDistro, package_manager: &str) -> Result<Vec<InstallStep>, String> {
+    let release = if distro.is("rhel") || distro.is("centos") {
+        distro.version_id.split('.').next().unwrap_or_default().to_string()
+    } else {
+        "9".to_string()
+    };
+    if !["8", "9"].contains(&release.as_str()) {
+        return Err(format!("{} has no MongoDB {} packages, supported RHEL releases: 8, 9", distro.name, LATEST_MONGODB_SERIES));
+    }
+    
+    let repo_file = format!(
+        "printf \"[mongodb-org-{series}]\\nname=MongoDB Repository\\nbaseurl=https://repo.mongodb.org/yum/redhat/{release}/mongodb-org/{series}/$(uname -m)/\\ngpgcheck=1\\nenabled=1\\ngpgkey=https://www.mongodb.org/static/pgp/server-{series}.asc\\n\" > /etc/yum.repos.d/mongodb-org-{series}.repo",
+        series = LATEST_MONGODB_SERIES,
+        release = release
+    );
+    
+    Ok(vec![
+        (repo_file, "Adding MongoDB repository", REPOSITORY_SETUP_FAILED),
+        (format!("{} install -y mongodb-org", package_manager), "Installing MongoDB packages", PACKAGE_INSTALL_FAILED),
+        ("systemctl daemon-reload && systemctl enable mongod && systemctl start mongod".to_string(), "Starting MongoDB service", SERVICE_START_FAILED),
+    ])
+}
+
+pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
+    // Pick the package manager and MongoDB repository for this distribution before asking for sudo
+    let distro = detect_distro()
+        .map_err(|e| emit_install_error(&app, UNSUPPORTED_DISTRO, 0, false, e))?;
+    let commands = install_steps(&distro)
+        .map_err(|e| emit_install_error(&app, UNSUPPORTED_DISTRO, 0, false, e))?;
+    emit_install_log(&app, 0, commands.len(), format!("Detected {} ({} packages)", distro.name, distro.package_manager()));
+    
+    run_install_steps(&app, &commands).await?;
+    
+    emit_install_log(&app, commands.len(), commands.len(), format!("MongoDB {} installation completed successfully", LATEST_MONGODB_SERIES));
+    Ok(())
+}
+
+// Run each step with sudo, asking for the password once. Stops at the first failing step.
+async fn run_install_steps(app: &AppHandle, commands: &[InstallStep]) -> Result<(), String> {
+    let password = get_sudo_password(app)
+        .await
+        .map_err(|e| emit_install_error(app, SUDO_REQUIRED, 0, true, format!("Sudo password not provided: {}", e)))?;
     
     // Execute each command separately to better identify failures
-    for (i, cmd) in commands.iter().enumerate() {
+    for (i, (cmd, cmd_desc, error_code)) in commands.iter().enumerate() {
         let step_num = i + 1;
-        let cmd_desc = match i {
-            0 => "Updating package database",
-            1 => "Installing dependencies",
-            2 => "Importing MongoDB GPG key",
-            3 => "Adding MongoDB repository",
-            4 => "Updating MongoDB package database",
-            5 => "Installing MongoDB packages",
-            6 => "Starting MongoDB service",
-            _ => "Unknown step"
-        };
         
-        app.emit("mongodb-install-log", format!("[Step {}/{}] {} - Starting", step_num, commands.len(), cmd_desc)).unwrap();
+        emit_install_log(app, step_num, commands.len(), format!("[Step {}/{}] {} - Starting", step_num, commands.len(), cmd_desc));
         
         let full_cmd = format!("echo {} | sudo -S bash -c '{}' 2>&1", password, cmd);
         
@@ -99,34 +246,56 @@ pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
             .command("bash")
             .args(["-c", &full_cmd])
             .spawn()
-            .map_err(|e| format!("Failed to spawn command at step {}: {}", step_num, e))?;
+            .map_err(|e| emit_install_error(app, COMMAND_FAILED, step_num, false, format!("Failed to spawn command at step {}: {}", step_num, e)))?;
+
+        // Keep the last output line so failures can be attributed (e.g. a full disk)
+        let mut last_output = String::new();
+        let mut last_percentage = -1.0;
 
         while let Some(event) = rx.recv().await {
             match event {
                 CommandEvent::Stdout(line) => {
-                    let log_line = format!("[Step {}/{}] {}", step_num, commands.len(), String::from_utf8_lossy(&line));
+                    let output = String::from_utf8_lossy(&line);
+                    // apt status lines drive the progress bar instead of the log
+                    if let Some(step_percentage) = apt_status_percentage(&output) {
+                        // Only report whole-percent changes so the UI isn't flooded
+                        if step_percentage.floor() > last_percentage {
+                            last_percentage = step_percentage.floor();
+                            app.emit("mongodb-install-percentage", InstallPercentage {
+                                step: step_num,
+                                total_steps: commands.len(),
+                                step_percentage,
+                                percentage: (i as f64 + step_percentage / 100.0) / commands.len() as f64 * 100.0,
+                            }).unwrap_or_default();
+                        }
+                        continue;
+                    }
+                    if !output.trim().is_empty() {
+                        last_output = output.trim().to_string();
+                    }
+                    let log_line = format!("[Step {}/{}] {}", step_num, commands.len(), output);
                     println!("BACKEND LOG: {}", log_line);
-                    app.emit("mongodb-install-log", log_line).unwrap();
+                    emit_install_log(app, step_num, commands.len(), log_line);
                 }
                 CommandEvent::Stderr(line) => {
                     let err_line = format!("[Step {}/{}] ERROR: {}", step_num, commands.len(), String::from_utf8_lossy(&line));
-                    println!("BACKEND ERROR: {}", err_line);
-                    app.emit("mongodb-install-error", err_line).unwrap();
+                    emit_install_error(app, INSTALL_WARNING, step_num, true, err_line);
                 }
                 CommandEvent::Terminated(status) => {
                     match status.code {
                         Some(0) => {
-                            app.emit("mongodb-install-log", format!("[Step {}/{}] {} - Completed", step_num, commands.len(), cmd_desc)).unwrap();
+                            emit_install_log(app, step_num, commands.len(), format!("[Step {}/{}] {} - Completed", step_num, commands.len(), cmd_desc));
                         },
                         Some(code) => {
-                            let error_msg = format!("Command failed with exit code {} during step {}: {}", code, step_num, cmd_desc);
-                            app.emit("mongodb-install-error", error_msg.clone()).unwrap();
-                            return Err(error_msg);
+                            let mut error_msg = format!("Command failed with exit code {} during step {}: {}", code, step_num, cmd_desc);
+                            if !last_output.is_empty() {
+                                error_msg.push_str(&format!(" (last output: {})", last_output));
+                            }
+                            return Err(emit_install_error(app, error_code, step_num, true, error_msg));
                         },
                         None => {
                             let error_msg = format!("Command was terminated by a signal during step {}: {}", step_num, cmd_desc);
-                            app.emit("mongodb-install-error", error_msg.clone()).unwrap();
-                            return Err(error_msg);
+                            return Err(emit_install_error(app, error_code, step_num, true, error_msg));
                         },
                     }
                 }
@@ -135,7 +304,50 @@ pub async fn install_mongodb(app: AppHandle) -> Result<(), String> {
         }
     }
 
-    app.emit("mongodb-install-log", "MongoDB 8.0 installation completed successfully").unwrap();
+    Ok(())
+}
+
+// Packages pinned to the target version, as MongoDB's upgrade docs do, so apt doesn't mix
+// component versions. mongosh is versioned separately and stays unpinned.
+const PINNED_MONGODB_PACKAGES: [&str; 5] = ["mongodb-org", "mongodb-org-database", "mongodb-org-server", "mongodb-org-mongos", "mongodb-org-tools"];
+
+// Switch the apt repository to the target's release series and install that exact version
+fn apt_upgrade_steps(distro: &LinuxDistro, installed_series: &str, target_series: &str, target_version: &str) -> Result<Vec<InstallStep>, String> {
+    let mut steps = Vec::new();
+    if installed_series != target_series {
+        steps.push((
+            format!("rm -f /etc/apt/sources.list.d/mongodb-org-{}.list", installed_series),
+            "Removing the old MongoDB repository",
+            REPOSITORY_SETUP_FAILED,
+        ));
+    }
+    steps.extend(apt_repository_steps(distro, target_series)?);
+    
+    let packages: Vec<String> = PINNED_MONGODB_PACKAGES.iter().map(|package| format!("{}={}", package, target_version)).collect();
+    steps.push((
+        format!("DEBIAN_FRONTEND=noninteractive apt-get {} install -y {} mongodb-mongosh", APT_STATUS_OPTION, packages.join(" ")),
+        "Upgrading MongoDB packages",
+        PACKAGE_INSTALL_FAILED,
+    ));
+    steps.push(("systemctl daemon-reload && systemctl restart mongod".to_string(), "Restarting MongoDB service", SERVICE_START_FAILED));
+    Ok(steps)
+}
+
+// Upgrade the apt-installed MongoDB to `target_version`. Only apt is supported for now.
+pub async fn upgrade_mongodb(app: AppHandle, installed_series: &str, target_series: &str, target_version: &str) -> Result<(), String> {
+    let distro = detect_distro()
+        .map_err(|e| emit_install_error(&app, UNSUPPORTED_DISTRO, 0, false, e))?;
+    if distro.package_manager() != "apt" {
+        return Err(emit_install_error(&app, UNSUPPORTED_DISTRO, 0, false, format!(
+            "In-app upgrades are only supported on apt-based distributions, not {}", distro.name
+        )));
+    }
+    let commands = apt_upgrade_steps(&distro, installed_series, target_series, target_version)
+        .map_err(|e| emit_install_error(&app, UNSUPPORTED_DISTRO, 0, false, e))?;
+    
+    run_install_steps(&app, &commands).await?;
+    
+    emit_install_log(&app, commands.len(), commands.len(), format!("MongoDB upgraded to {}", target_version));
     Ok(())
 }
 
@@ -197,4 +409,342 @@ pub async fn is_mongodb_installed() -> bool {
     
     println!("Final MongoDB installation status on Ubuntu: {}", result);
     result
-}
\ No newline at end of file
+}
+
+// Version of the apt-packaged mongod, e.g. "8.0.6" from "db version v8.0.6"
+pub(super) fn system_mongod_version() -> Option<String> {
+    use std::process::Command;
+    
+    let output = Command::new(PACKAGED_MONGOD_PATH).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    output_str
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("db version v"))
+        .map(|version| version.trim().to_string())
+}
+
+pub async fn list_installed_versions() -> Result<Vec<String>, String> {
+    let mut versions = Vec::new();
+    
+    if let Some(version) = system_mongod_version() {
+        versions.push(version);
+    }
+    
+    if Path::new(TARBALL_INSTALL_DIR).exists() {
+        let entries = fs::read_dir(TARBALL_INSTALL_DIR)
+            .map_err(|e| format!("Failed to read {}: {}", TARBALL_INSTALL_DIR, e))?;
+        
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.join("bin").join("mongod").exists() {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if !versions.iter().any(|v| v == name) {
+                    versions.push(name.to_string());
+                }
+            }
+        }
+    }
+    
+    println!("Installed MongoDB versions on Linux: {:?}", versions);
+    Ok(versions)
+}
+
+pub async fn select_active_version(app: AppHandle, version: &str) -> Result<(), String> {
+    let password = get_sudo_password(&app)
+        .await
+        .map_err(|e| emit_install_error(&app, SUDO_REQUIRED, 0, true, format!("Sudo password not provided: {}", e)))?;
+    let version_bin = format!("{}/{}/bin", TARBALL_INSTALL_DIR, version);
+    let override_file = format!("{}/active-version.conf", SERVICE_OVERRIDE_DIR);
+    
+    let cmd = if Path::new(&version_bin).join("mongod").exists() {
+        // Point the shell binaries and the systemd unit at the tarball install
+        format!(
+            "ln -sf {bin}/* /usr/local/bin/ && mkdir -p {dir} && printf \"[Service]\\nExecStart=\\nExecStart={bin}/mongod --config /etc/mongod.conf\\n\" > {file} && systemctl daemon-reload && systemctl restart mongod",
+            bin = version_bin,
+            dir = SERVICE_OVERRIDE_DIR,
+            file = override_file
+        )
+    } else {
+        // The packaged version is active once the tarball symlinks and unit override are gone
+        format!(
+            "find /usr/local/bin -lname \"{}/*\" -delete && rm -f {} && systemctl daemon-reload && systemctl restart mongod",
+            TARBALL_INSTALL_DIR,
+            override_file
+        )
+    };
+    
+    run_privileged(&app, &password, &cmd, &format!("Switching to MongoDB {}", version)).await?;
+    
+    emit_install_log(&app, 0, 0, format!("MongoDB {} is now the active version", version));
+    Ok(())
+}
+
+// Data directory from storage.dbPath in /etc/mongod.conf, or the package default
+pub fn data_directory() -> std::path::PathBuf {
+    fs::read_to_string(MONGOD_CONFIG_PATH)
+        .ok()
+        .and_then(|contents| config::get_value(&contents, "storage.dbPath"))
+        .filter(|path| !path.is_empty())
+        .unwrap_or_else(|| "/var/lib/mongodb".to_string())
+        .into()
+}
+
+// Whether a mongod process is running, either the given pid or any mongod at all
+pub fn mongod_process_alive(pid: Option<i32>) -> bool {
+    use std::process::Command;
+    
+    match pid {
+        Some(pid) => Path::new(&format!("/proc/{}", pid)).exists(),
+        None => Command::new("pgrep")
+            .args(["-x", "mongod"])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false),
+    }
+}
+
+// The data directory belongs to the mongodb user, so removing the lock needs sudo
+pub async fn remove_lock_file(app: &AppHandle, lock_path: &Path) -> Result<(), String> {
+    let password = get_sudo_password(app)
+        .await
+        .map_err(|e| emit_install_error(app, SUDO_REQUIRED, 0, true, format!("Sudo password not provided: {}", e)))?;
+    
+    let cmd = format!("rm -f \"{}\"", lock_path.display());
+    run_privileged(app, &password, &cmd, "Removing stale mongod.lock").await
+}
+
+// Physical memory from /proc/meminfo, which reports MemTotal in kB
+pub fn total_memory_bytes() -> Result<u64, String> {
+    let meminfo = fs::read_to_string("/proc/meminfo")
+        .map_err(|e| format!("Failed to read /proc/meminfo: {}", e))?;
+    
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+        .ok_or_else(|| "MemTotal not found in /proc/meminfo".to_string())
+}
+
+// Apply settings to /etc/mongod.conf and restart mongod so they take effect.
+// The file is edited as the current user and copied into place with sudo.
+pub async fn update_mongod_config(app: AppHandle, settings: &[(&str, String)], description: &str) -> Result<(), String> {
+    let contents = fs::read_to_string(MONGOD_CONFIG_PATH)
+        .map_err(|e| emit_install_error(&app, CONFIG_UPDATE_FAILED, 0, false, format!("Failed to read {}: {}", MONGOD_CONFIG_PATH, e)))?;
+    
+    let mut updated = contents;
+    for (key, value) in settings {
+        updated = config::set_value(&updated, key, value);
+        emit_install_log(&app, 0, 0, format!("[{}] Setting {} to {}", description, key, value));
+    }
+    
+    let staged_path = std::env::temp_dir().join(format!("mongod-{}.conf", Uuid::new_v4()));
+    fs::write(&staged_path, updated)
+        .map_err(|e| emit_install_error(&app, CONFIG_UPDATE_FAILED, 0, true, format!("Failed to stage updated config: {}", e)))?;
+    
+    let password = get_sudo_password(&app)
+        .await
+        .map_err(|e| emit_install_error(&app, SUDO_REQUIRED, 0, true, format!("Sudo password not provided: {}", e)))?;
+    
+    let cmd = format!(
+        "cp {staged} {config} && chmod 644 {config} && systemctl restart mongod",
+        staged = staged_path.display(),
+        config = MONGOD_CONFIG_PATH
+    );
+    let result = run_privileged(&app, &password, &cmd, description).await;
+    
+    let _ = fs::remove_file(&staged_path);
+    result
+}
+
+// Run a single command as root, streaming its output to the install log events
+async fn run_privileged(app: &AppHandle, password: &str, cmd: &str, description: &str) -> Result<(), String> {
+    emit_install_log(app, 0, 0, format!("{} - Starting", description));
+    
+    let full_cmd = format!("echo {} | sudo -S bash -c '{}' 2>&1", password, cmd);
+    
+    let (mut rx, _child) = app.shell()
+        .command("bash")
+        .args(["-c", &full_cmd])
+        .spawn()
+        .map_err(|e| emit_install_error(app, COMMAND_FAILED, 0, false, format!("Failed to spawn command for {}: {}", description, e)))?;
+    
+    let mut last_output = String::new();
+    
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(line) => {
+                let output = String::from_utf8_lossy(&line);
+                if !output.trim().is_empty() {
+                    last_output = output.trim().to_string();
+                }
+                let log_line = format!("[{}] {}", description, output);
+                println!("BACKEND LOG: {}", log_line);
+                emit_install_log(app, 0, 0, log_line);
+            }
+            CommandEvent::Stderr(line) => {
+                let err_line = format!("[{}] ERROR: {}", description, String::from_utf8_lossy(&line));
+                emit_install_error(app, INSTALL_WARNING, 0, true, err_line);
+            }
+            CommandEvent::Terminated(status) => {
+                match status.code {
+                    Some(0) => {
+                        emit_install_log(app, 0, 0, format!("{} - Completed", description));
+                    },
+                    Some(code) => {
+                        let mut error_msg = format!("Command failed with exit code {}: {}", code, description);
+                        if !last_output.is_empty() {
+                            error_msg.push_str(&format!(" (last output: {})", last_output));
+                        }
+                        return Err(emit_install_error(app, COMMAND_FAILED, 0, true, error_msg));
+                    },
+                    None => {
+                        let error_msg = format!("Command was terminated by a signal: {}", description);
+                        return Err(emit_install_error(app, COMMAND_FAILED, 0, true, error_msg));
+                    },
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    Ok(())
+}
+
+// Addresses from `hostname -I`, which lists every configured address except loopback
+pub fn local_ip_addresses() -> Result<Vec<String>, String> {
+    use std::process::Command;
+    
+    let output = Command::new("hostname")
+        .arg("-I")
+        .output()
+        .map_err(|e| format!("Failed to list IP addresses: {}", e))?;
+    
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(|addr| addr.to_string())
+        .collect())
+}
+
+// Check ufw first and fall back to the raw iptables INPUT chain when ufw isn't installed.
+// Both need root to read the rules, in which case the status is Unknown.
+pub fn firewall_status(port: u16) -> FirewallStatus {
+    use std::process::Command;
+    
+    match Command::new("ufw").arg("status").output() {
+        Ok(output) if output.status.success() => return ufw_firewall_status(&String::from_utf8_lossy(&output.stdout), port),
+        Ok(_) => return FirewallStatus::Unknown { reason: "Reading ufw rules needs root, check with `sudo ufw status`".to_string() },
+        Err(_) => {}
+    }
+    
+    match Command::new("iptables").args(["-S", "INPUT"]).output() {
+        Ok(output) if output.status.success() => iptables_firewall_status(&String::from_utf8_lossy(&output.stdout), port),
+        Ok(_) => FirewallStatus::Unknown { reason: "Reading iptables rules needs root, check with `sudo iptables -S INPUT`".to_string() },
+        Err(e) => FirewallStatus::Unknown { reason: format!("Neither ufw nor iptables could be run: {}", e) },
+    }
+}
+
+// Parse `ufw status`: a "Status:" line, then rules like "27017/tcp   ALLOW   Anywhere"
+fn ufw_firewall_status(output: &str, port: u16) -> FirewallStatus {
+    if output.lines().any(|line| line.trim() == "Status: inactive") {
+        return FirewallStatus::Disabled;
+    }
+    
+    let allowed = output.lines().any(|line| {
+        let mut columns = line.split_whitespace();
+        let (Some(target), Some(action)) = (columns.next(), columns.next()) else {
+            return false;
+        };
+        let (ports, protocol) = target.split_once('/').unwrap_or((target, "tcp"));
+        action == "ALLOW" && protocol == "tcp" && port_list_contains(ports, port)
+    });
+    if allowed { FirewallStatus::Allowed } else { FirewallStatus::Blocked }
+}
+
+// Parse `iptables -S INPUT`, e.g. "-P INPUT DROP" and "-A INPUT -p tcp -m tcp --dport 27017 -j ACCEPT"
+fn iptables_firewall_status(output: &str, port: u16) -> FirewallStatus {
+    let allowed = output.lines().any(|line| {
+        let args: Vec<&str> = line.split_whitespace().collect();
+        let accepts = args.windows(2).any(|pair| pair == ["-j", "ACCEPT"]);
+        let matches_port = args.windows(2).any(|pair| {
+            matches!(pair[0], "--dport" | "--dports") && port_list_contains(pair[1], port)
+        });
+        accepts && matches_port
+    });
+    if allowed {
+        return FirewallStatus::Allowed;
+    }
+    
+    let filters = output.lines().any(|line| {
+        line.starts_with("-P INPUT DROP") || line.contains("-j DROP") || line.contains("-j REJECT")
+    });
+    if filters { FirewallStatus::Blocked } else { FirewallStatus::Disabled }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn apt_status_percentage_maps_download_and_unpack_halves() {
+        assert_eq!(apt_status_percentage("dlstatus:1:0:Retrieving file 1 of 7"), Some(0.0));
+        assert_eq!(apt_status_percentage("dlstatus:3:45.2:Retrieving file 3 of 7"), Some(22.6));
+        assert_eq!(apt_status_percentage("pmstatus:mongodb-org-server:60:Unpacking mongodb-org-server (amd64)"), Some(80.0));
+        assert_eq!(apt_status_percentage("pmstatus:dpkg-exec:100:Running dpkg\n"), Some(100.0));
+    }
+    
+    #[test]
+    fn apt_status_percentage_ignores_other_lines() {
+        assert_eq!(apt_status_percentage("pmerror:mongodb-org-server:60:dpkg returned an error code"), None);
+        assert_eq!(apt_status_percentage("media-change:disk:label:Insert disk"), None);
+        assert_eq!(apt_status_percentage("Reading package lists..."), None);
+        assert_eq!(apt_status_percentage("dlstatus:3:not-a-number:Retrieving file"), None);
+    }
+    
+    #[test]
+    fn ufw_firewall_status_reads_rules() {
+        let inactive = "Status: inactive\n";
+        assert_eq!(ufw_firewall_status(inactive, 27017), FirewallStatus::Disabled);
+        
+        let output = "\
+Status: active
+
+To                         Action      From
+--                         ------      ----
+22/tcp                     ALLOW       Anywhere
+27000:28000/tcp            ALLOW       192.168.1.0/24
+27019/udp                  ALLOW       Anywhere
+27020/tcp                  DENY        Anywhere
+22/tcp (v6)                ALLOW       Anywhere (v6)
+";
+        assert_eq!(ufw_firewall_status(output, 27017), FirewallStatus::Allowed);
+        assert_eq!(ufw_firewall_status(output, 28001), FirewallStatus::Blocked);
+        assert_eq!(ufw_firewall_status("Status: active\n\n27020/tcp DENY Anywhere\n", 27020), FirewallStatus::Blocked);
+        assert_eq!(ufw_firewall_status("Status: active\n\n27019/udp ALLOW Anywhere\n", 27019), FirewallStatus::Blocked);
+    }
+    
+    #[test]
+    fn iptables_firewall_status_reads_rules() {
+        let open = "-P INPUT ACCEPT\n";
+        assert_eq!(iptables_firewall_status(open, 27017), FirewallStatus::Disabled);
+        
+        let output = "\
+-P INPUT DROP
+-A INPUT -i lo -j ACCEPT
+-A INPUT -p tcp -m tcp --dport 22 -j ACCEPT
+-A INPUT -p tcp -m multiport --dports 27017,27018 -j ACCEPT
+";
+        assert_eq!(iptables_firewall_status(output, 27017), FirewallStatus::Allowed);
+        assert_eq!(iptables_firewall_status(output, 27019), FirewallStatus::Blocked);
+        assert_eq!(
+            iptables_firewall_status("-P INPUT ACCEPT\n-A INPUT -p tcp --dport 27017 -j REJECT\n", 27017),
+            FirewallStatus::Blocked
+        );
+    }
+}
@@ -9,14 +9,11 @@ use serde::{Serialize, Deserialize};
 use std::path::Path;
 use std::fs;
 use uuid::Uuid;
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct InstallProgress {
-    step: usize,
-    total_steps: usize,
-    message: String,
-    is_error: bool,
-}
+use super::{config, port_list_contains, version_key, wait_for_mongodb_ready, FirewallStatus, LATEST_MONGODB_VERSION};
+use super::{
+    emit_install_error, emit_install_log, COMMAND_FAILED, CONFIG_UPDATE_FAILED, DIRECTORY_CREATE_FAILED, DOWNLOAD_FAILED, INSTALLER_FAILED, INSTALL_IN_PROGRESS,
+    INSTALL_WARNING, PATH_UPDATE_FAILED, REBOOT_REQUIRED, SERVICE_START_FAILED,
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct DownloadProgress {
@@ -25,13 +22,19 @@ pub struct DownloadProgress {
     percentage: f64,
 }
 
+const MONGODB_SERVER_DIR: &str = r"C:\Program Files\MongoDB\Server";
+const DEFAULT_DATA_DIR: &str = r"C:\data\db";
+
+// Consecutive malformed progress lines tolerated before falling back to raw byte counts
+const MAX_PROGRESS_PARSE_FAILURES: usize = 3;
+
 pub async fn install_mongodb(app: &AppHandle) -> Result<(), String> {
     // Define the MongoDB Windows download and installation parameters
-    let mongodb_version = "8.0.6";
+    let mongodb_version = LATEST_MONGODB_VERSION;
     let download_url = format!("https://fastdl.mongodb.org/windows/mongodb-windows-x86_64-{}-signed.msi", mongodb_version);
     let installer_filename = format!("mongodb-installer-{}.msi", Uuid::new_v4());
     let installer_path = std::env::temp_dir().join(installer_filename);
-    let data_dir = r"C:\data\db";
+    let data_dir = DEFAULT_DATA_DIR;
     let mongo_bin_path = format!(r"C:\Program Files\MongoDB\Server\{}\bin", mongodb_version);
 
     // Define the steps for MongoDB installation
@@ -45,7 +48,7 @@ pub async fn install_mongodb(app: &AppHandle) -> Result<(), String> {
     // Step 1: Create data directory
     emit_progress(app, 1, total_steps, "Creating MongoDB data directory", false);
     create_directory(&data_dir)
-        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        .map_err(|e| emit_install_error(app, DIRECTORY_CREATE_FAILED, 1, false, format!("Failed to create data directory: {}", e)))?;
     
     // Step 2: Download MongoDB MSI installer
     emit_progress(app, 2, total_steps, "Downloading MongoDB installer", false);
@@ -53,45 +56,39 @@ pub async fn install_mongodb(app: &AppHandle) -> Result<(), String> {
     let installer_str = installer_path.to_str().unwrap();
     download_file_with_progress(app, &download_url, installer_str)
         .await
-        .map_err(|e| format!("Failed to download MongoDB installer: {}", e))?;
+        .map_err(|e| emit_install_error(app, DOWNLOAD_FAILED, 2, true, format!("Failed to download MongoDB installer: {}", e)))?;
     
     // Rest of the function remains unchanged...
     // Step 3: Install MongoDB silently
     emit_progress(app, 3, total_steps, "Installing MongoDB", false);
     install_mongodb_msi(app, installer_str)
         .await
-        .map_err(|e| format!("Failed to install MongoDB: {}", e))?;
+        .map_err(|e| emit_install_error(app, e.code, 3, e.recoverable, format!("Failed to install MongoDB: {}", e.message)))?;
     
     // Step 4: Add MongoDB to PATH
     emit_progress(app, 4, total_steps, "Adding MongoDB to system PATH", false);
     add_to_path(app, &mongo_bin_path)
         .await
-        .map_err(|e| format!("Failed to add MongoDB to PATH: {}", e))?;
+        .map_err(|e| emit_install_error(app, PATH_UPDATE_FAILED, 4, true, format!("Failed to add MongoDB to PATH: {}", e)))?;
     
     // Step 5: Start MongoDB service
     emit_progress(app, 5, total_steps, "Starting MongoDB service", false);
     start_mongodb_service(app, &mongo_bin_path, &data_dir)
         .await
-        .map_err(|e| format!("Failed to start MongoDB service: {}", e))?;
+        .map_err(|e| emit_install_error(app, SERVICE_START_FAILED, 5, true, format!("Failed to start MongoDB service: {}", e)))?;
 
     emit_progress(app, total_steps, total_steps, "MongoDB installation completed successfully", false);
     Ok(())
 }
 
 fn emit_progress(app: &AppHandle, step: usize, total_steps: usize, message: &str, is_error: bool) {
-    let progress = InstallProgress {
-        step,
-        total_steps,
-        message: message.to_string(),
-        is_error,
-    };
-    
     if is_error {
-        app.emit("mongodb-install-error", progress).unwrap_or_default();
-    } else {
-        app.emit("mongodb-install-log", progress).unwrap_or_default();
+        emit_install_error(app, INSTALL_WARNING, step, true, message.to_string());
+        return;
     }
     
+    emit_install_log(app, step, total_steps, message.to_string());
+    
     println!("[{}/{}] {}", step, total_steps, message);
 }
 
@@ -301,6 +298,8 @@ async fn download_file_with_progress(app: &AppHandle, url: &str, out_path: &str)
         .map_err(|e| format!("Failed to spawn download script: {}", e))?;
 
     let mut last_progress_percentage = 0.0;
+    let mut progress_parse_failures = 0;
+    let mut using_progress_fallback = false;
     
     while let Some(event) = rx.recv().await {
         match event {
@@ -310,19 +309,46 @@ async fn download_file_with_progress(app: &AppHandle, url: &str, out_path: &str)
                 
                 if line_str.contains("PROGRESS:") {
                     let json_str = line_str.replace("PROGRESS:", "").trim().to_string();
-                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                        if let (Some(bytes), Some(total), Some(percentage)) = (
-                            parsed["bytesDownloaded"].as_u64(),
-                            parsed["totalBytes"].as_u64(),
-                            parsed["percentage"].as_f64()
-                        ) {
-                            let progress = DownloadProgress {
-                                bytes_downloaded: bytes,
-                                total_bytes: total, 
-                                percentage,
-                            };
+                    match serde_json::from_str::<serde_json::Value>(&json_str) {
+                        Ok(parsed) => {
+                            progress_parse_failures = 0;
+                            if let (Some(bytes), Some(total), Some(percentage)) = (
+                                parsed["bytesDownloaded"].as_u64(),
+                                parsed["totalBytes"].as_u64(),
+                                parsed["percentage"].as_f64()
+                            ) {
+                                let progress = DownloadProgress {
+                                    bytes_downloaded: bytes,
+                                    total_bytes: total, 
+                                    percentage,
+                                };
+                                
+                                last_progress_percentage = percentage;
+                                app.emit("mongodb-download-progress", progress.clone()).unwrap_or_default();
+                            }
+                        }
+                        Err(e) => {
+                            progress_parse_failures += 1;
+                            println!("Failed to parse download progress JSON ({}): {}", e, json_str);
                             
-                            app.emit("mongodb-download-progress", progress.clone()).unwrap_or_default();
+                            // After repeated failures, estimate progress from the raw byte count instead
+                            if progress_parse_failures >= MAX_PROGRESS_PARSE_FAILURES {
+                                if !using_progress_fallback {
+                                    using_progress_fallback = true;
+                                    emit_install_log(app, 2, 5, format!(
+                                        "Download progress data is malformed ({} consecutive parse failures), estimating progress from raw byte counts",
+                                        progress_parse_failures
+                                    ));
+                                }
+                                
+                                if let Some(progress) = estimate_progress_from_raw(&json_str, total_bytes) {
+                                    // Never move the progress bar backwards on a bad estimate
+                                    if progress.percentage >= last_progress_percentage {
+                                        last_progress_percentage = progress.percentage;
+                                        app.emit("mongodb-download-progress", progress).unwrap_or_default();
+                                    }
+                                }
+                            }
                         }
                     }
                 } else if line_str.contains("COMPLETE:") {
@@ -339,25 +365,13 @@ async fn download_file_with_progress(app: &AppHandle, url: &str, out_path: &str)
                     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
                 } else if line_str.contains("METHOD:") {
                     let method_msg = format!("Download method: {}", line_str.replace("METHOD:", "").trim());
-                    app.emit("mongodb-install-log", InstallProgress {
-                        step: 2,
-                        total_steps: 5,
-                        message: method_msg,
-                        is_error: false,
-                    }).unwrap_or_default();
+                    emit_install_log(app, 2, 5, method_msg);
                 }
             }
             CommandEvent::Stderr(line) => {
                 let err_line = String::from_utf8_lossy(&line).trim().to_string();
                 let err_msg = format!("Download error: {}", err_line);
-                println!("{}", err_msg);
-                
-                app.emit("mongodb-install-error", InstallProgress {
-                    step: 2,
-                    total_steps: 5,
-                    message: err_msg,
-                    is_error: true,
-                }).unwrap_or_default();
+                emit_install_error(app, INSTALL_WARNING, 2, true, err_msg);
             }
             CommandEvent::Terminated(status) => {
                 if status.code.unwrap_or(-1) != 0 {
@@ -377,7 +391,70 @@ async fn download_file_with_progress(app: &AppHandle, url: &str, out_path: &str)
     Ok(())
 }
 
-async fn install_mongodb_msi(app: &AppHandle, installer_path: &str) -> Result<(), String> {
+// Pull the byte count out of a progress line that failed to parse as JSON,
+// e.g. `{"bytesDownloaded":1048576,"totalBytes":...` with a mangled number elsewhere
+fn estimate_progress_from_raw(raw: &str, total_bytes: u64) -> Option<DownloadProgress> {
+    let key = "bytesDownloaded";
+    let start = raw.find(key)? + key.len();
+    let digits: String = raw[start..]
+        .chars()
+        .skip_while(|c| matches!(c, '"' | ':' | ' '))
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let bytes_downloaded = digits.parse::<u64>().ok()?;
+    
+    let percentage = if total_bytes > 0 {
+        ((bytes_downloaded as f64 / total_bytes as f64) * 100.0).min(100.0)
+    } else {
+        0.0
+    };
+    
+    Some(DownloadProgress {
+        bytes_downloaded,
+        total_bytes,
+        percentage,
+    })
+}
+
+// Why the MSI step failed, with the install error code the frontend should branch on
+struct MsiFailure {
+    code: &'static str,
+    recoverable: bool,
+    message: String,
+}
+
+impl MsiFailure {
+    fn installer(message: String) -> Self {
+        Self { code: INSTALLER_FAILED, recoverable: true, message }
+    }
+}
+
+// Map a Windows Installer exit code to an outcome. 3010 is a success that needs a restart: it is
+// reported as a recoverable REBOOT_REQUIRED error so the frontend can prompt for a reboot,
+// without failing the install.
+fn check_msi_exit_code(app: &AppHandle, exit_code: i32) -> Result<(), MsiFailure> {
+    match exit_code {
+        0 => Ok(()),
+        3010 => {
+            emit_install_error(app, REBOOT_REQUIRED, 3, true, "MongoDB was installed, but Windows must be restarted to complete the installation".to_string());
+            Ok(())
+        }
+        1602 => Err(MsiFailure::installer("The installation was cancelled (MSI exit code 1602)".to_string())),
+        1603 => Err(MsiFailure {
+            code: INSTALLER_FAILED,
+            recoverable: false,
+            message: "Windows Installer hit a fatal error (MSI exit code 1603). Check the Windows Event Log for details".to_string(),
+        }),
+        1618 => Err(MsiFailure {
+            code: INSTALL_IN_PROGRESS,
+            recoverable: true,
+            message: "Another installation is already in progress (MSI exit code 1618). Wait for it to finish and try again".to_string(),
+        }),
+        code => Err(MsiFailure::installer(format!("Windows Installer failed with exit code {}", code))),
+    }
+}
+
+async fn install_mongodb_msi(app: &AppHandle, installer_path: &str) -> Result<(), MsiFailure> {
     // Step 1: Inform the user we're starting the manual installation
     emit_progress(
         app, 
@@ -387,22 +464,31 @@ async fn install_mongodb_msi(app: &AppHandle, installer_path: &str) -> Result<()
         false
     );
 
-    // Step 2: Open the MSI file with the default program (Windows Installer)
+    // Step 2: Open the MSI file with the default program (Windows Installer). -PassThru gives us
+    // the msiexec process, whose exit code is the MSI result rather than PowerShell's own.
     let (mut rx, _child) = app.shell()
         .command("powershell")
         .args([
             "-Command",
             &format!(
-                "Start-Process '{}' -Wait",
+                "$process = Start-Process '{}' -Wait -PassThru; Write-Output \"MSI_EXIT_CODE=$($process.ExitCode)\"",
                 installer_path.replace('\\', "\\\\")
             )
         ])
         .spawn()
-        .map_err(|e| format!("Failed to open the MongoDB installer: {}", e))?;
+        .map_err(|e| MsiFailure::installer(format!("Failed to open the MongoDB installer: {}", e)))?;
+    
+    let mut msi_exit_code = None;
 
     // Step 3: Wait for the process to complete
     while let Some(event) = rx.recv().await {
         match event {
+            CommandEvent::Stdout(line) => {
+                let out_line = String::from_utf8_lossy(&line).trim().to_string();
+                if let Some(code) = out_line.strip_prefix("MSI_EXIT_CODE=") {
+                    msi_exit_code = code.trim().parse::<i32>().ok();
+                }
+            }
             CommandEvent::Stderr(line) => {
                 let err_line = String::from_utf8_lossy(&line).trim().to_string();
                 if !err_line.is_empty() {
@@ -411,8 +497,11 @@ async fn install_mongodb_msi(app: &AppHandle, installer_path: &str) -> Result<()
                 }
             }
             CommandEvent::Terminated(status) => {
-                if status.code.unwrap_or(-1) != 0 {
-                    return Err(format!("Installation process terminated with code: {:?}", status.code));
+                match msi_exit_code {
+                    Some(exit_code) => check_msi_exit_code(app, exit_code)?,
+                    None => {
+                        return Err(MsiFailure::installer(format!("Installation process terminated with code: {:?}", status.code)));
+                    }
                 }
                 emit_progress(app, 3, 5, "MongoDB installation wizard completed", false);
             }
@@ -434,7 +523,7 @@ async fn install_mongodb_msi(app: &AppHandle, installer_path: &str) -> Result<()
             "Test-Path 'C:\\Program Files\\MongoDB\\Server'"
         ])
         .spawn()
-        .map_err(|e| format!("Failed to verify installation: {}", e))?;
+        .map_err(|e| MsiFailure::installer(format!("Failed to verify installation: {}", e)))?;
     
     let mut is_installed = false;
     
@@ -497,23 +586,12 @@ async fn add_to_path(app: &AppHandle, bin_path: &str) -> Result<(), String> {
             CommandEvent::Stdout(line) => {
                 let output = String::from_utf8_lossy(&line).trim().to_string();
                 println!("PATH update: {}", output);
-                app.emit("mongodb-install-log", InstallProgress {
-                    step: 4,
-                    total_steps: 5,
-                    message: output,
-                    is_error: false,
-                }).unwrap_or_default();
+                emit_install_log(app, 4, 5, output);
             }
             CommandEvent::Stderr(line) => {
                 let err_line = String::from_utf8_lossy(&line).trim().to_string();
                 let err_msg = format!("PATH update error: {}", err_line);
-                println!("{}", err_msg);
-                app.emit("mongodb-install-error", InstallProgress {
-                    step: 4,
-                    total_steps: 5,
-                    message: err_msg,
-                    is_error: true,
-                }).unwrap_or_default();
+                emit_install_error(app, INSTALL_WARNING, 4, true, err_msg);
             }
             CommandEvent::Terminated(status) => {
                 if status.code.unwrap_or(-1) != 0 {
@@ -527,6 +605,40 @@ async fn add_to_path(app: &AppHandle, bin_path: &str) -> Result<(), String> {
     Ok(())
 }
 
+// How long a manually started mongod gets to begin listening, including journal recovery
+const MONGOD_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+// A readable reason from a mongod output line that reports a startup problem. Handles both the
+// JSON log format (4.4+), using its error/fatal severities, and plain-text lines from older versions.
+fn mongod_startup_error(line: &str) -> Option<String> {
+    let line = line.trim();
+    
+    if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) {
+        if !matches!(entry["s"].as_str(), Some("E") | Some("F")) {
+            return None;
+        }
+        let attr = &entry["attr"];
+        let detail = attr["error"]["errmsg"].as_str()
+            .or_else(|| attr["error"].as_str())
+            .or_else(|| attr["reason"].as_str());
+        let msg = entry["msg"].as_str().unwrap_or("mongod reported an error");
+        return Some(match detail {
+            Some(detail) => format!("{}: {}", msg, detail),
+            None => msg.to_string(),
+        });
+    }
+    
+    const KNOWN_ERRORS: [&str; 6] = [
+        "Data directory",
+        "Permission denied",
+        "Address already in use",
+        "No space left on device",
+        "Unable to lock the lock file",
+        "exception in initAndListen",
+    ];
+    KNOWN_ERRORS.iter().any(|known| line.contains(known)).then(|| line.to_string())
+}
+
 async fn start_mongodb_service(app: &AppHandle, bin_path: &str, data_dir: &str) -> Result<(), String> {
     // Try to start the MongoDB service first
     let (mut rx, _child) = app.shell()
@@ -562,38 +674,54 @@ async fn start_mongodb_service(app: &AppHandle, bin_path: &str, data_dir: &str)
         emit_progress(app, 5, 5, "MongoDB service not found. Starting mongod manually...", false);
         
         let mongod_path = format!("{}\\mongod.exe", bin_path);
+        if !Path::new(&mongod_path).exists() {
+            return Err(format!("mongod.exe not found at {}", mongod_path));
+        }
+        
+        // Run mongod as our own child so its output (and early exit) can be observed
         let (mut rx, _child) = app.shell()
-            .command("powershell")
-            .args([
-                "-Command",
-                &format!(
-                    "if (Test-Path '{}') {{ Start-Process '{}' -ArgumentList '--dbpath', '{}' -NoNewWindow -PassThru }}",
-                    mongod_path.replace('\\', "\\\\"),
-                    mongod_path.replace('\\', "\\\\"),
-                    data_dir.replace('\\', "\\\\")
-                )
-            ])
+            .command(&mongod_path)
+            .args(["--dbpath", data_dir])
             .spawn()
             .map_err(|e| format!("Failed to start mongod manually: {}", e))?;
-
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stderr(line) => {
-                    let err_line = format!("Mongod start error: {}", String::from_utf8_lossy(&line));
-                    println!("{}", err_line);
-                    app.emit("mongodb-install-error", InstallProgress {
-                        step: 5,
-                        total_steps: 5,
-                        message: err_line,
-                        is_error: true,
-                    }).unwrap_or_default();
-                }
-                CommandEvent::Terminated(status) => {
-                    if status.code.unwrap_or(-1) != 0 {
-                        return Err(format!("Mongod start failed with exit code: {:?}", status.code));
+        
+        let ready = wait_for_mongodb_ready(MONGOD_START_TIMEOUT);
+        tokio::pin!(ready);
+        let mut startup_error = None;
+        
+        loop {
+            tokio::select! {
+                is_ready = &mut ready => {
+                    if !is_ready {
+                        return Err(match startup_error {
+                            Some(reason) => format!("mongod did not start listening: {}", reason),
+                            None => format!("mongod did not start listening within {}s", MONGOD_START_TIMEOUT.as_secs()),
+                        });
                     }
+                    
+                    // Keep draining mongod's output so it never blocks on a full pipe
+                    tauri::async_runtime::spawn(async move {
+                        while rx.recv().await.is_some() {}
+                    });
+                    emit_progress(app, 5, 5, "mongod is accepting connections", false);
+                    break;
                 }
-                _ => {}
+                event = rx.recv() => match event {
+                    Some(CommandEvent::Stdout(line)) | Some(CommandEvent::Stderr(line)) => {
+                        if let Some(reason) = mongod_startup_error(&String::from_utf8_lossy(&line)) {
+                            emit_install_error(app, INSTALL_WARNING, 5, true, format!("mongod: {}", reason));
+                            startup_error = Some(reason);
+                        }
+                    }
+                    Some(CommandEvent::Terminated(status)) => {
+                        return Err(match startup_error {
+                            Some(reason) => format!("mongod exited during startup: {}", reason),
+                            None => format!("mongod exited during startup with code: {:?}", status.code),
+                        });
+                    }
+                    Some(_) => {}
+                    None => return Err("Lost mongod's output before it started listening".to_string()),
+                },
             }
         }
     }
@@ -646,4 +774,345 @@ pub async fn is_mongodb_installed() -> bool {
     
     println!("Final MongoDB installation status on Windows: {}", result);
     result
-}
\ No newline at end of file
+}
+
+pub async fn list_installed_versions() -> Result<Vec<String>, String> {
+    let server_dir = Path::new(MONGODB_SERVER_DIR);
+    if !server_dir.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let entries = fs::read_dir(server_dir)
+        .map_err(|e| format!("Failed to read {}: {}", MONGODB_SERVER_DIR, e))?;
+    
+    let mut versions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.join("bin").join("mongod.exe").exists() {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            versions.push(name.to_string());
+        }
+    }
+    
+    println!("Installed MongoDB versions on Windows: {:?}", versions);
+    Ok(versions)
+}
+
+pub async fn select_active_version(app: &AppHandle, version: &str) -> Result<(), String> {
+    let bin_path = format!(r"{}\{}\bin", MONGODB_SERVER_DIR, version);
+    let total_steps = 2;
+    
+    // Step 1: Replace any MongoDB server bin directory on the machine PATH with the selected one
+    emit_progress(app, 1, total_steps, &format!("Pointing PATH at MongoDB {}", version), false);
+    run_powershell_script(app, 1, total_steps, &format!(
+        "$ErrorActionPreference = 'Stop';
+         try {{
+            $entries = [Environment]::GetEnvironmentVariable('Path', 'Machine') -split ';' |
+                Where-Object {{ $_ -and ($_ -notlike '{server_dir}\\*') }};
+            $entries = @($entries) + '{bin}';
+            [Environment]::SetEnvironmentVariable('Path', ($entries -join ';'), 'Machine');
+            Write-Output 'PATH now points at {bin}';
+         }} catch {{
+            Write-Error \"Failed to update PATH: $($_.Exception.Message)\";
+            exit 1;
+         }}",
+        server_dir = MONGODB_SERVER_DIR,
+        bin = bin_path
+    )).await?;
+    
+    // Step 2: Re-point the MongoDB service binary and restart it, if the service exists
+    emit_progress(app, 2, total_steps, &format!("Restarting MongoDB service on version {}", version), false);
+    run_powershell_script(app, 2, total_steps, &format!(
+        "$ErrorActionPreference = 'Stop';
+         try {{
+            $service = Get-Service -Name 'MongoDB' -ErrorAction SilentlyContinue;
+            if (-not $service) {{
+               Write-Output 'MongoDB service not installed, skipping service update';
+               exit 0;
+            }}
+            Stop-Service -Name 'MongoDB' -Force;
+            $imagePath = '\"{bin}\\mongod.exe\" --config \"{bin}\\mongod.cfg\" --service';
+            Set-ItemProperty -Path 'HKLM:\\SYSTEM\\CurrentControlSet\\Services\\MongoDB' -Name 'ImagePath' -Value $imagePath;
+            Start-Service -Name 'MongoDB';
+            Write-Output 'MongoDB service restarted';
+         }} catch {{
+            Write-Error \"Failed to switch MongoDB service: $($_.Exception.Message)\";
+            exit 1;
+         }}",
+        bin = bin_path
+    )).await?;
+    
+    emit_progress(app, total_steps, total_steps, &format!("MongoDB {} is now the active version", version), false);
+    Ok(())
+}
+
+// Install a newer release's MSI next to the current one, then move PATH and the MongoDB
+// service over to it with select_active_version. The old version's files stay in place.
+pub async fn upgrade_mongodb(app: &AppHandle, target_series: &str, target_version: &str) -> Result<(), String> {
+    let download_url = format!("https://fastdl.mongodb.org/windows/mongodb-windows-x86_64-{}-signed.msi", target_version);
+    let installer_path = std::env::temp_dir().join(format!("mongodb-installer-{}.msi", Uuid::new_v4()));
+    let installer_str = installer_path.to_str()
+        .ok_or_else(|| "Temporary directory path is not valid UTF-8".to_string())?;
+    let total_steps = 3;
+    
+    emit_progress(app, 1, total_steps, &format!("Downloading MongoDB {} installer", target_version), false);
+    download_file_with_progress(app, &download_url, installer_str)
+        .await
+        .map_err(|e| emit_install_error(app, DOWNLOAD_FAILED, 1, true, format!("Failed to download MongoDB {} installer: {}", target_version, e)))?;
+    
+    emit_progress(app, 2, total_steps, &format!("Installing MongoDB {}", target_version), false);
+    install_mongodb_msi(app, installer_str)
+        .await
+        .map_err(|e| emit_install_error(app, e.code, 2, e.recoverable, format!("Failed to install MongoDB {}: {}", target_version, e.message)))?;
+    
+    emit_progress(app, 3, total_steps, &format!("Switching to MongoDB {}", target_series), false);
+    select_active_version(app, target_series).await
+}
+
+// mongod.cfg of the newest installed server version, which the MSI-registered service reads
+fn mongod_config_path() -> Result<std::path::PathBuf, String> {
+    let newest = fs::read_dir(MONGODB_SERVER_DIR)
+        .map_err(|e| format!("Failed to read {}: {}", MONGODB_SERVER_DIR, e))?
+        .flatten()
+        .filter(|entry| entry.path().join("bin").join("mongod.cfg").exists())
+        .max_by_key(|entry| version_key(&entry.file_name().to_string_lossy()));
+    
+    newest
+        .map(|entry| entry.path().join("bin").join("mongod.cfg"))
+        .ok_or_else(|| format!("No mongod.cfg found under {}", MONGODB_SERVER_DIR))
+}
+
+// Data directory from storage.dbPath in mongod.cfg, or the one install_mongodb creates
+pub fn data_directory() -> std::path::PathBuf {
+    mongod_config_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| config::get_value(&contents, "storage.dbPath"))
+        .filter(|path| !path.is_empty())
+        .unwrap_or_else(|| DEFAULT_DATA_DIR.to_string())
+        .into()
+}
+
+// Whether a mongod process is running, either the given pid or any mongod.exe at all
+pub fn mongod_process_alive(pid: Option<i32>) -> bool {
+    use std::process::Command;
+    
+    let (filter, expected) = match pid {
+        Some(pid) => (format!("PID eq {}", pid), pid.to_string()),
+        None => ("IMAGENAME eq mongod.exe".to_string(), "mongod.exe".to_string()),
+    };
+    
+    // tasklist prints an informational line instead of a row when nothing matches
+    Command::new("tasklist")
+        .args(["/FI", &filter, "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&expected))
+        .unwrap_or(false)
+}
+
+pub async fn remove_lock_file(app: &AppHandle, lock_path: &Path) -> Result<(), String> {
+    fs::remove_file(lock_path)
+        .map_err(|e| emit_install_error(app, COMMAND_FAILED, 0, true, format!("Failed to remove {}: {}", lock_path.display(), e)))
+}
+
+// Physical memory as reported by Win32_ComputerSystem, in bytes
+pub fn total_memory_bytes() -> Result<u64, String> {
+    use std::process::Command;
+    
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", "(Get-CimInstance Win32_ComputerSystem).TotalPhysicalMemory"])
+        .output()
+        .map_err(|e| format!("Failed to query physical memory: {}", e))?;
+    
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    value.parse::<u64>()
+        .map_err(|_| format!("Unexpected physical memory value: '{}'", value))
+}
+
+// Apply settings to mongod.cfg and restart the MongoDB service so they take effect
+pub async fn update_mongod_config(app: &AppHandle, settings: &[(&str, String)], description: &str) -> Result<(), String> {
+    let total_steps = 2;
+    
+    emit_progress(app, 1, total_steps, description, false);
+    let config_path = mongod_config_path()
+        .map_err(|e| emit_install_error(app, CONFIG_UPDATE_FAILED, 1, false, e))?;
+    
+    let contents = fs::read_to_string(&config_path)
+        .map_err(|e| emit_install_error(app, CONFIG_UPDATE_FAILED, 1, false, format!("Failed to read {}: {}", config_path.display(), e)))?;
+    
+    let mut updated = contents;
+    for (key, value) in settings {
+        updated = config::set_value(&updated, key, value);
+        emit_progress(app, 1, total_steps, &format!("Setting {} to {}", key, value), false);
+    }
+    
+    fs::write(&config_path, updated)
+        .map_err(|e| emit_install_error(app, CONFIG_UPDATE_FAILED, 1, false, format!("Failed to write {} (run the app as administrator): {}", config_path.display(), e)))?;
+    
+    emit_progress(app, 2, total_steps, "Restarting MongoDB service", false);
+    run_powershell_script(app, 2, total_steps, "Restart-Service -Name 'MongoDB' -Force -ErrorAction Stop; 'MongoDB service restarted'")
+        .await
+        .map_err(|e| emit_install_error(app, SERVICE_START_FAILED, 2, true, e))?;
+    
+    Ok(())
+}
+
+// Run an inline PowerShell script, forwarding its output as progress for the given step
+async fn run_powershell_script(app: &AppHandle, step: usize, total_steps: usize, script: &str) -> Result<(), String> {
+    let (mut rx, _child) = app.shell()
+        .command("powershell")
+        .args(["-Command", script])
+        .spawn()
+        .map_err(|e| format!("Failed to spawn PowerShell: {}", e))?;
+    
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(line) => {
+                let output = String::from_utf8_lossy(&line).trim().to_string();
+                if !output.is_empty() {
+                    emit_progress(app, step, total_steps, &output, false);
+                }
+            }
+            CommandEvent::Stderr(line) => {
+                let err_line = String::from_utf8_lossy(&line).trim().to_string();
+                if !err_line.is_empty() {
+                    emit_progress(app, step, total_steps, &format!("PowerShell error: {}", err_line), true);
+                }
+            }
+            CommandEvent::Terminated(status) => {
+                match status.code {
+                    Some(0) => {},
+                    code => return Err(format!("PowerShell script failed with exit code: {:?}", code)),
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    Ok(())
+}
+
+// IPv4 and IPv6 addresses of every interface, as reported by Get-NetIPAddress
+pub fn local_ip_addresses() -> Result<Vec<String>, String> {
+    use std::process::Command;
+    
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", "Get-NetIPAddress | Select-Object -ExpandProperty IPAddress"])
+        .output()
+        .map_err(|e| format!("Failed to list IP addresses: {}", e))?;
+    
+    // IPv6 addresses can carry a zone suffix such as "%12"
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|addr| addr.trim().split('%').next().unwrap_or("").to_string())
+        .filter(|addr| !addr.is_empty())
+        .collect())
+}
+
+// Check the active profile's firewall state, then look for an enabled inbound allow rule that
+// covers the port. netsh output is localized, so on non-English Windows this reports Unknown.
+pub fn firewall_status(port: u16) -> FirewallStatus {
+    use std::process::Command;
+    
+    let state = match Command::new("netsh").args(["advfirewall", "show", "currentprofile", "state"]).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).to_string(),
+        Ok(output) => return FirewallStatus::Unknown { reason: String::from_utf8_lossy(&output.stdout).trim().to_string() },
+        Err(e) => return FirewallStatus::Unknown { reason: format!("Failed to run netsh: {}", e) },
+    };
+    let state = state.lines().find_map(|line| line.trim().strip_prefix("State").map(|value| value.trim().to_string()));
+    match state.as_deref() {
+        Some("OFF") => return FirewallStatus::Disabled,
+        Some("ON") => {}
+        _ => return FirewallStatus::Unknown { reason: "Unrecognized netsh firewall state output".to_string() },
+    }
+    
+    match Command::new("netsh").args(["advfirewall", "firewall", "show", "rule", "name=all", "dir=in"]).output() {
+        Ok(output) if output.status.success() => netsh_rules_status(&String::from_utf8_lossy(&output.stdout), port),
+        Ok(output) => FirewallStatus::Unknown { reason: String::from_utf8_lossy(&output.stdout).trim().to_string() },
+        Err(e) => FirewallStatus::Unknown { reason: format!("Failed to run netsh: {}", e) },
+    }
+}
+
+// Each rule in netsh's listing starts with "Rule Name:" followed by "Key: value" lines
+fn netsh_rules_status(output: &str, port: u16) -> FirewallStatus {
+    let mut rules: Vec<std::collections::HashMap<&str, &str>> = Vec::new();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        if key.trim() == "Rule Name" {
+            rules.push(std::collections::HashMap::new());
+        }
+        if let Some(rule) = rules.last_mut() {
+            rule.insert(key.trim(), value.trim());
+        }
+    }
+    
+    let allowed = rules.iter().any(|rule| {
+        rule.get("Enabled") == Some(&"Yes")
+            && rule.get("Action") == Some(&"Allow")
+            && matches!(rule.get("Protocol"), Some(&"TCP") | Some(&"Any"))
+            && rule.get("LocalPort").is_some_and(|ports| port_list_contains(ports, port))
+    });
+    if allowed { FirewallStatus::Allowed } else { FirewallStatus::Blocked }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const NETSH_OUTPUT: &str = "\
+Rule Name:                            MongoDB
+----------------------------------------------------------------------
+Enabled:                              Yes
+Direction:                            In
+Profiles:                             Domain,Private
+Grouping:
+LocalIP:                              Any
+RemoteIP:                             Any
+Protocol:                             TCP
+LocalPort:                            27017
+RemotePort:                           Any
+Edge traversal:                       No
+Action:                               Allow
+
+Rule Name:                            Mongo shards
+----------------------------------------------------------------------
+Enabled:                              No
+Direction:                            In
+Protocol:                             TCP
+LocalPort:                            27018-27020
+Action:                               Allow
+
+Rule Name:                            Block config server
+----------------------------------------------------------------------
+Enabled:                              Yes
+Direction:                            In
+Protocol:                             TCP
+LocalPort:                            27021
+Action:                               Block
+Ok.
+";
+    
+    #[test]
+    fn netsh_rules_status_needs_enabled_allow_rule() {
+        assert_eq!(netsh_rules_status(NETSH_OUTPUT, 27017), FirewallStatus::Allowed);
+        // Disabled rule
+        assert_eq!(netsh_rules_status(NETSH_OUTPUT, 27019), FirewallStatus::Blocked);
+        // Block action
+        assert_eq!(netsh_rules_status(NETSH_OUTPUT, 27021), FirewallStatus::Blocked);
+        assert_eq!(netsh_rules_status("No rules match the specified criteria.\n", 27017), FirewallStatus::Blocked);
+    }
+    
+    #[test]
+    fn netsh_rules_status_accepts_any_protocol_and_port_lists() {
+        let output = "Rule Name: Any\nEnabled: Yes\nProtocol: Any\nLocalPort: Any\nAction: Allow\n";
+        assert_eq!(netsh_rules_status(output, 27017), FirewallStatus::Allowed);
+        
+        let output = "Rule Name: List\nEnabled: Yes\nProtocol: TCP\nLocalPort: 80,443,27000-28000\nAction: Allow\n";
+        assert_eq!(netsh_rules_status(output, 27017), FirewallStatus::Allowed);
+        
+        let output = "Rule Name: Udp\nEnabled: Yes\nProtocol: UDP\nLocalPort: 27017\nAction: Allow\n";
+        assert_eq!(netsh_rules_status(output, 27017), FirewallStatus::Blocked);
+    }
+}